@@ -1,14 +1,36 @@
 import cv2
-from fer.fer import FER
-from deepface import DeepFace
 import json
 import sys
 import time
 from collections import deque
 
+PROTOCOL_VERSION = 1
+SERVICE_VERSION = "1.1.0"
+
+# 模型导入失败时不直接崩溃，而是在hello握手中报告
+IMPORT_ERRORS = []
+
+try:
+    from fer.fer import FER
+except Exception as e:
+    FER = None
+    IMPORT_ERRORS.append(f"FER import failed: {e}")
+
+try:
+    from deepface import DeepFace
+except Exception as e:
+    DeepFace = None
+    IMPORT_ERRORS.append(f"DeepFace import failed: {e}")
+
 class EmotionDetector:
     def __init__(self):
-        self.fer_detector = FER()
+        self.errors = list(IMPORT_ERRORS)
+        self.fer_detector = None
+        if FER is not None:
+            try:
+                self.fer_detector = FER()
+            except Exception as e:
+                self.errors.append(f"FER init failed: {e}")
         self.cap = None
         self.face_cascade = cv2.CascadeClassifier(cv2.data.haarcascades + 'haarcascade_frontalface_default.xml')
         self.face_detection_window = deque(maxlen=50)
@@ -59,10 +81,27 @@ class EmotionDetector:
                     return False
         return self.cap.isOpened()
 
+    def hello(self):
+        """握手信息：协议版本、可用后端和摄像头状态"""
+        camera_available = self.init_camera()
+        errors = list(self.errors)
+        if not camera_available:
+            errors.append("Cannot open camera")
+        return {
+            "protocol_version": PROTOCOL_VERSION,
+            "service_version": SERVICE_VERSION,
+            "backends": {
+                "fer": self.fer_detector is not None,
+                "deepface": DeepFace is not None,
+            },
+            "camera_available": camera_available,
+            "errors": errors,
+        }
+
     def detect_emotion(self):
         try:
             if not self.init_camera():
-                return {"error": "Cannot open camera"}
+                return {"error": "Cannot open camera", "code": "camera"}
 
             ret, frame = self.cap.read()
             if not ret:
@@ -70,7 +109,7 @@ class EmotionDetector:
                 if self.cap is not None:
                     self.cap.release()
                     self.cap = None
-                return {"error": "Cannot read frame"}
+                return {"error": "Cannot read frame", "code": "camera"}
 
             gray = cv2.cvtColor(frame, cv2.COLOR_BGR2GRAY)
             faces = self.face_cascade.detectMultiScale(gray, 1.1, 9)
@@ -103,44 +142,46 @@ class EmotionDetector:
             for (x, y, w, h) in faces:
                 face = frame[y:y + h, x:x + w]
 
-                try:
-                    emotion_fer, score_fer = self.fer_detector.top_emotion(face)
-
-                    # 映射FER情绪
-                    mapped_fer = self.map_emotion_to_frontend(emotion_fer) if emotion_fer else 'calm'
-
-                    # 根据工作时长判断最终情绪
-                    final_emotion = self.determine_final_emotion(mapped_fer, work_duration / 60)
-
-                    emotion_data = {
-                        "emotion": final_emotion,
-                        "confidence": float(score_fer) if score_fer else 0.0,
-                        "source": "fer"
-                    }
-
-                    result["emotions"].append(emotion_data)
-                except Exception as e:
-                    sys.stderr.write(f"FER detection error: {e}\n")
-                    sys.stderr.flush()
-
-                try:
-                    analysis = DeepFace.analyze(face, actions=['emotion'], enforce_detection=False)
-                    emotion_deepface = analysis[0]['dominant_emotion']
-                    score_deepface = analysis[0]['emotion'][emotion_deepface]
-
-                    # 映射DeepFace情绪
-                    mapped_deepface = self.map_emotion_to_frontend(emotion_deepface)
-                    final_deepface = self.determine_final_emotion(mapped_deepface, work_duration / 60)
-
-                    # 添加DeepFace结果
-                    result["emotions"].append({
-                        "emotion": final_deepface,
-                        "confidence": float(score_deepface),
-                        "source": "deepface"
-                    })
-                except Exception as e:
-                    sys.stderr.write(f"DeepFace detection error: {e}\n")
-                    sys.stderr.flush()
+                if self.fer_detector is not None:
+                    try:
+                        emotion_fer, score_fer = self.fer_detector.top_emotion(face)
+
+                        # 映射FER情绪
+                        mapped_fer = self.map_emotion_to_frontend(emotion_fer) if emotion_fer else 'calm'
+
+                        # 根据工作时长判断最终情绪
+                        final_emotion = self.determine_final_emotion(mapped_fer, work_duration / 60)
+
+                        emotion_data = {
+                            "emotion": final_emotion,
+                            "confidence": float(score_fer) if score_fer else 0.0,
+                            "source": "fer"
+                        }
+
+                        result["emotions"].append(emotion_data)
+                    except Exception as e:
+                        sys.stderr.write(f"FER detection error: {e}\n")
+                        sys.stderr.flush()
+
+                if DeepFace is not None:
+                    try:
+                        analysis = DeepFace.analyze(face, actions=['emotion'], enforce_detection=False)
+                        emotion_deepface = analysis[0]['dominant_emotion']
+                        score_deepface = analysis[0]['emotion'][emotion_deepface]
+
+                        # 映射DeepFace情绪
+                        mapped_deepface = self.map_emotion_to_frontend(emotion_deepface)
+                        final_deepface = self.determine_final_emotion(mapped_deepface, work_duration / 60)
+
+                        # 添加DeepFace结果
+                        result["emotions"].append({
+                            "emotion": final_deepface,
+                            "confidence": float(score_deepface),
+                            "source": "deepface"
+                        })
+                    except Exception as e:
+                        sys.stderr.write(f"DeepFace detection error: {e}\n")
+                        sys.stderr.flush()
 
             return result
 
@@ -151,7 +192,7 @@ class EmotionDetector:
             if self.cap is not None:
                 self.cap.release()
                 self.cap = None
-            return {"error": f"OpenCV error: {str(e)}"}
+            return {"error": f"OpenCV error: {str(e)}", "code": "camera"}
         except Exception as e:
             sys.stderr.write(f"Unexpected error in detect_emotion: {e}\n")
             sys.stderr.flush()
@@ -167,6 +208,25 @@ class EmotionDetector:
         if self.cap:
             self.cap.release()
 
+# Keep the real stdout for protocol frames; anything FER, DeepFace or
+# TensorFlow prints goes to stderr instead of corrupting the stream
+protocol_out = sys.stdout
+sys.stdout = sys.stderr
+
+
+def send_response(request_id, result=None, error=None, code=None):
+    """写出一行协议响应 {"v", "id", "result"|"error"[, "code"]}"""
+    frame = {"v": PROTOCOL_VERSION, "id": request_id}
+    if error is not None:
+        frame["error"] = error
+        if code is not None:
+            frame["code"] = code
+    else:
+        frame["result"] = result
+    protocol_out.write(json.dumps(frame) + "\n")
+    protocol_out.flush()
+
+
 if __name__ == "__main__":
     detector = EmotionDetector()
     sys.stderr.write("Emotion service started\n")
@@ -174,20 +234,44 @@ if __name__ == "__main__":
 
     try:
         while True:
-            line = sys.stdin.readline().strip()
+            line = sys.stdin.readline()
+            if not line:
+                # stdin closed, parent is gone
+                break
+            line = line.strip()
             if not line:
                 continue
-            if line == "DETECT":
+
+            try:
+                request = json.loads(line)
+            except json.JSONDecodeError:
+                sys.stderr.write(f"Malformed request: {line}\n")
+                sys.stderr.flush()
+                send_response(None, error="malformed request")
+                continue
+
+            request_id = request.get("id")
+            cmd = request.get("cmd")
+
+            if request.get("v") != PROTOCOL_VERSION:
+                send_response(request_id, error=f"unsupported protocol version: {request.get('v')}")
+            elif cmd == "hello":
+                send_response(request_id, result=detector.hello())
+            elif cmd == "detect":
                 result = detector.detect_emotion()
-                print(json.dumps(result))
-                sys.stdout.flush()
-            elif line == "QUIT":
+                if "error" in result:
+                    # 检测失败作为协议错误返回，code 供 Rust 端区分摄像头故障
+                    send_response(request_id, error=result["error"], code=result.get("code"))
+                else:
+                    send_response(request_id, result=result)
+            elif cmd == "quit":
                 sys.stderr.write("Shutting down emotion service\n")
                 sys.stderr.flush()
                 break
             else:
-                sys.stderr.write(f"Unknown command: {line}\n")
+                sys.stderr.write(f"Unknown command: {cmd}\n")
                 sys.stderr.flush()
+                send_response(request_id, error=f"unknown command: {cmd}")
     except KeyboardInterrupt:
         pass
     except Exception as e:
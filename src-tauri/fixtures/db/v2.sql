@@ -0,0 +1,53 @@
+-- emotions.db at schema v2: adds emotion_entries and the all_emotions view
+CREATE TABLE emotion_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    datetime TEXT NOT NULL,
+    fer_emotion TEXT NOT NULL,
+    fer_confidence REAL NOT NULL,
+    deepface_emotion TEXT,
+    deepface_confidence REAL,
+    mapped_emotion TEXT NOT NULL,
+    work_minutes REAL NOT NULL,
+    is_away INTEGER NOT NULL,
+    has_face INTEGER NOT NULL,
+    CHECK (fer_confidence >= 0 AND fer_confidence <= 1),
+    CHECK (deepface_confidence IS NULL OR (deepface_confidence >= 0 AND deepface_confidence <= 1)),
+    CHECK (mapped_emotion IN ('happy', 'calm', 'worried', 'tired')),
+    CHECK (is_away IN (0, 1)),
+    CHECK (has_face IN (0, 1))
+);
+CREATE INDEX idx_timestamp ON emotion_records(timestamp);
+CREATE INDEX idx_datetime ON emotion_records(datetime);
+CREATE INDEX idx_mapped_emotion ON emotion_records(mapped_emotion);
+CREATE INDEX idx_datetime_emotion ON emotion_records(datetime, mapped_emotion);
+
+INSERT INTO emotion_records (timestamp, datetime, fer_emotion, fer_confidence, deepface_emotion, deepface_confidence, mapped_emotion, work_minutes, is_away, has_face)
+VALUES
+    (1735693200, '2025-01-01 09:00:00', 'happy', 0.91, 'happy', 0.88, 'happy', 0.0, 0, 1),
+    (1735693500, '2025-01-01 09:05:00', 'calm', 0.74, NULL, NULL, 'calm', 5.0, 0, 1),
+    (1735693800, '2025-01-01 09:10:00', 'worried', 0.66, 'worried', 0.52, 'worried', 10.0, 0, 1);
+
+CREATE TABLE emotion_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    datetime TEXT NOT NULL,
+    emotion TEXT NOT NULL,
+    confidence REAL NOT NULL,
+    source TEXT NOT NULL,
+    CHECK (emotion IN ('happy', 'calm', 'worried', 'tired')),
+    CHECK (confidence >= 0 AND confidence <= 1),
+    CHECK (source IN ('manual', 'import'))
+);
+CREATE INDEX idx_entries_timestamp ON emotion_entries(timestamp);
+CREATE INDEX idx_entries_datetime ON emotion_entries(datetime);
+CREATE VIEW all_emotions AS
+    SELECT NULL AS id, timestamp, datetime, mapped_emotion AS emotion,
+           fer_confidence AS confidence, 'detected' AS source
+    FROM emotion_records
+    UNION ALL
+    SELECT id, timestamp, datetime, emotion, confidence, source
+    FROM emotion_entries;
+
+INSERT INTO emotion_entries (timestamp, datetime, emotion, confidence, source)
+VALUES (1735694100, '2025-01-01 09:15:00', 'calm', 1.0, 'manual');
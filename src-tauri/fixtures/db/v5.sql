@@ -0,0 +1,107 @@
+-- emotions.db at schema v5: adds daily_summaries
+CREATE TABLE emotion_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    datetime TEXT NOT NULL,
+    fer_emotion TEXT NOT NULL,
+    fer_confidence REAL NOT NULL,
+    deepface_emotion TEXT,
+    deepface_confidence REAL,
+    mapped_emotion TEXT NOT NULL,
+    work_minutes REAL NOT NULL,
+    is_away INTEGER NOT NULL,
+    has_face INTEGER NOT NULL,
+    CHECK (fer_confidence >= 0 AND fer_confidence <= 1),
+    CHECK (deepface_confidence IS NULL OR (deepface_confidence >= 0 AND deepface_confidence <= 1)),
+    CHECK (mapped_emotion IN ('happy', 'calm', 'worried', 'tired')),
+    CHECK (is_away IN (0, 1)),
+    CHECK (has_face IN (0, 1))
+);
+CREATE INDEX idx_timestamp ON emotion_records(timestamp);
+CREATE INDEX idx_datetime ON emotion_records(datetime);
+CREATE INDEX idx_mapped_emotion ON emotion_records(mapped_emotion);
+CREATE INDEX idx_datetime_emotion ON emotion_records(datetime, mapped_emotion);
+
+INSERT INTO emotion_records (timestamp, datetime, fer_emotion, fer_confidence, deepface_emotion, deepface_confidence, mapped_emotion, work_minutes, is_away, has_face)
+VALUES
+    (1735693200, '2025-01-01 09:00:00', 'happy', 0.91, 'happy', 0.88, 'happy', 0.0, 0, 1),
+    (1735693500, '2025-01-01 09:05:00', 'calm', 0.74, NULL, NULL, 'calm', 5.0, 0, 1),
+    (1735693800, '2025-01-01 09:10:00', 'worried', 0.66, 'worried', 0.52, 'worried', 10.0, 0, 1);
+
+CREATE TABLE emotion_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    datetime TEXT NOT NULL,
+    emotion TEXT NOT NULL,
+    confidence REAL NOT NULL,
+    source TEXT NOT NULL,
+    CHECK (emotion IN ('happy', 'calm', 'worried', 'tired')),
+    CHECK (confidence >= 0 AND confidence <= 1),
+    CHECK (source IN ('manual', 'import'))
+);
+CREATE INDEX idx_entries_timestamp ON emotion_entries(timestamp);
+CREATE INDEX idx_entries_datetime ON emotion_entries(datetime);
+CREATE VIEW all_emotions AS
+    SELECT NULL AS id, timestamp, datetime, mapped_emotion AS emotion,
+           fer_confidence AS confidence, 'detected' AS source
+    FROM emotion_records
+    UNION ALL
+    SELECT id, timestamp, datetime, emotion, confidence, source
+    FROM emotion_entries;
+
+INSERT INTO emotion_entries (timestamp, datetime, emotion, confidence, source)
+VALUES (1735694100, '2025-01-01 09:15:00', 'calm', 1.0, 'manual');
+
+CREATE TABLE mood_checkins (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    datetime TEXT NOT NULL,
+    emotion TEXT NOT NULL,
+    intensity INTEGER NOT NULL,
+    note TEXT,
+    CHECK (emotion IN ('happy', 'calm', 'worried', 'tired')),
+    CHECK (intensity BETWEEN 1 AND 10)
+);
+CREATE INDEX idx_checkins_datetime ON mood_checkins(datetime);
+
+INSERT INTO mood_checkins (timestamp, datetime, emotion, intensity, note)
+VALUES (1735696800, '2025-01-01 10:00:00', 'tired', 6, 'after standup');
+
+CREATE TABLE emotion_rollups (
+    datetime TEXT PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    happy_count INTEGER NOT NULL,
+    calm_count INTEGER NOT NULL,
+    worried_count INTEGER NOT NULL,
+    tired_count INTEGER NOT NULL,
+    mean_confidence REAL NOT NULL,
+    focus_minutes REAL NOT NULL,
+    CHECK (happy_count >= 0 AND calm_count >= 0 AND worried_count >= 0 AND tired_count >= 0),
+    CHECK (mean_confidence >= 0 AND mean_confidence <= 1),
+    CHECK (focus_minutes >= 0)
+);
+CREATE INDEX idx_rollups_timestamp ON emotion_rollups(timestamp);
+
+INSERT INTO emotion_rollups (datetime, timestamp, happy_count, calm_count, worried_count, tired_count, mean_confidence, focus_minutes)
+VALUES ('2024-12-01 09:00:00', 1733043600, 3, 1, 0, 0, 0.75, 2.5);
+
+CREATE TABLE daily_summaries (
+    date TEXT PRIMARY KEY,
+    emotion_index REAL NOT NULL,
+    stress_level REAL NOT NULL,
+    total_records INTEGER NOT NULL,
+    valid_records INTEGER NOT NULL,
+    focus_sessions INTEGER NOT NULL,
+    focus_minutes REAL NOT NULL,
+    happy_count INTEGER NOT NULL,
+    calm_count INTEGER NOT NULL,
+    worried_count INTEGER NOT NULL,
+    tired_count INTEGER NOT NULL,
+    checkin_count INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    CHECK (emotion_index >= 1 AND emotion_index <= 10),
+    CHECK (stress_level >= 0 AND stress_level <= 100)
+);
+
+INSERT INTO daily_summaries (date, emotion_index, stress_level, total_records, valid_records, focus_sessions, focus_minutes, happy_count, calm_count, worried_count, tired_count, checkin_count, updated_at)
+VALUES ('2024-12-01', 7.2, 18.5, 4, 4, 1, 2.5, 3, 1, 0, 0, 0, 1733097600);
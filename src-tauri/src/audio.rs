@@ -1,86 +1,720 @@
-use rodio::{Decoder, OutputStream, Sink, Source};
+use chrono::{DateTime, Local};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
 use std::fs::File;
 use std::io::BufReader;
-use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-pub struct AudioPlayer {
-    sink: Option<Sink>,
-    _stream: Option<OutputStream>,
+use crate::sleep_timer::{PlaybackStatus, SleepTimer};
+use crate::sounds::{self, FadeSettings, MixLayer, MixerState, NoiseColor, Sound, SoundInfo, SoundKind, SoundLibrary};
+
+const NOISE_SAMPLE_RATE: u32 = 44_100;
+
+/// Voss-McCartney rows; each is refreshed half as often as the one before,
+/// which gives a 1/f spectrum down to about NOISE_SAMPLE_RATE / 2^PINK_ROWS
+const PINK_ROWS: usize = 16;
+
+/// Per-sample decay of the brown noise integrator. Without it the walk would
+/// drift off to full scale; it flattens the spectrum below about 35 Hz.
+const BROWN_LEAK: f32 = 0.995;
+
+/// Endless mono noise, synthesized sample by sample
+pub struct Noise {
+    color: NoiseColor,
+    // xorshift32 state, never zero
+    state: u32,
+    rows: [f32; PINK_ROWS],
+    counter: u32,
+    brown: f32,
 }
 
-// SAFETY: AudioPlayer is protected by Mutex in AppState, ensuring single-threaded access
-unsafe impl Send for AudioPlayer {}
-unsafe impl Sync for AudioPlayer {}
+impl Noise {
+    pub fn new(color: NoiseColor) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.subsec_nanos())
+            .unwrap_or(0);
+        Self::with_seed(color, nanos)
+    }
+
+    pub fn with_seed(color: NoiseColor, seed: u32) -> Self {
+        let mut noise = Self {
+            color,
+            state: seed.max(1),
+            rows: [0.0; PINK_ROWS],
+            counter: 0,
+            brown: 0.0,
+        };
+        for row in 0..PINK_ROWS {
+            noise.rows[row] = noise.white();
+        }
+        noise
+    }
 
-impl AudioPlayer {
-    pub fn new() -> Self {
-        Self {
-            sink: None,
-            _stream: None,
+    /// Uniform in [-1, 1)
+    fn white(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+
+    fn pink(&mut self) -> f32 {
+        // Row n changes every 2^n samples
+        self.counter = self.counter.wrapping_add(1);
+        let row = self.counter.trailing_zeros() as usize;
+        if row < PINK_ROWS {
+            self.rows[row] = self.white();
         }
+        let sum: f32 = self.rows.iter().sum();
+        (sum + self.white()) / 8.0
     }
 
-    pub fn play_white_noise(&mut self, volume: f32) -> Result<(), String> {
-        self.stop();
+    fn brown(&mut self) -> f32 {
+        self.brown = self.brown * BROWN_LEAK + self.white() * 0.05;
+        self.brown
+    }
+}
 
-        let (stream, stream_handle) = OutputStream::try_default()
-            .map_err(|e| format!("Failed to create audio stream: {}", e))?;
+impl Iterator for Noise {
+    type Item = f32;
 
-        let sink = Sink::try_new(&stream_handle)
-            .map_err(|e| format!("Failed to create sink: {}", e))?;
+    fn next(&mut self) -> Option<f32> {
+        // Each color is scaled to roughly the same loudness
+        let sample = match self.color {
+            NoiseColor::White => self.white() * 0.5,
+            NoiseColor::Pink => self.pink(),
+            NoiseColor::Brown => self.brown(),
+        };
+        Some(sample.clamp(-1.0, 1.0))
+    }
+}
+
+impl Source for Noise {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        NOISE_SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// How often the audio thread updates sink volumes while a fade runs
+const FADE_STEP: Duration = Duration::from_millis(20);
+
+/// A linear gain ramp
+#[derive(Debug, Clone, Copy)]
+struct Envelope {
+    from: f32,
+    to: f32,
+    started: Instant,
+    duration: Duration,
+}
+
+impl Envelope {
+    fn new(from: f32, to: f32, duration: Duration) -> Self {
+        Self { from, to, started: Instant::now(), duration }
+    }
+
+    fn gain_at(&self, now: Instant) -> f32 {
+        let elapsed = now.saturating_duration_since(self.started);
+        if elapsed >= self.duration {
+            return self.to;
+        }
+        let progress = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        self.from + (self.to - self.from) * progress
+    }
+
+    fn finished_at(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started) >= self.duration
+    }
+}
+
+/// A sink whose volume is following an envelope
+struct Fade {
+    sink: Rc<Sink>,
+    /// The layer's effective volume, which the envelope scales
+    level: f32,
+    envelope: Envelope,
+    /// Fading out: stop the sink once silent
+    stop_at_end: bool,
+}
+
+/// Sink volumes following their envelopes; the audio thread steps them
+/// every FADE_STEP while any are in progress
+#[derive(Default)]
+struct Fader {
+    fades: Vec<Fade>,
+}
+
+impl Fader {
+    /// Ramp `sink` from silence up to `level`
+    fn fade_in(&mut self, sink: &Rc<Sink>, level: f32, duration: Duration) {
+        if duration.is_zero() {
+            sink.set_volume(level);
+            return;
+        }
+        sink.set_volume(0.0);
+        self.fades.push(Fade { sink: sink.clone(), level, envelope: Envelope::new(0.0, 1.0, duration), stop_at_end: false });
+    }
+
+    /// Ramp `sink` down from wherever it is and stop it
+    fn fade_out(&mut self, sink: Rc<Sink>, level: f32, duration: Duration) {
+        let from = self.cancel(&sink).unwrap_or(1.0);
+        if duration.is_zero() || level == 0.0 {
+            sink.stop();
+            return;
+        }
+        self.fades.push(Fade { sink, level, envelope: Envelope::new(from, 0.0, duration), stop_at_end: true });
+    }
 
-        // Try to find the white noise file
-        // In development: src/assets/sounds/rain.mp3
-        // In production: assets/sounds/rain.mp3 (bundled with app)
-        let possible_paths = vec![
-            PathBuf::from("src/assets/sounds/rain.mp3"),
-            PathBuf::from("assets/sounds/rain.mp3"),
-            PathBuf::from("../src/assets/sounds/rain.mp3"),
-        ];
-
-        let mut audio_file = None;
-        for path in possible_paths {
-            if path.exists() {
-                audio_file = Some(path);
-                break;
+    /// Change the level of a fading sink; returns false when it is not fading
+    fn set_level(&mut self, sink: &Rc<Sink>, level: f32) -> bool {
+        match self.fades.iter_mut().find(|fade| Rc::ptr_eq(&fade.sink, sink)) {
+            Some(fade) => {
+                fade.level = level;
+                true
             }
+            None => false,
         }
+    }
 
-        let audio_path = audio_file.ok_or_else(|| {
-            "White noise file not found. Please ensure rain.mp3 exists in assets/sounds/".to_string()
-        })?;
+    fn is_idle(&self) -> bool {
+        self.fades.is_empty()
+    }
 
-        let file = File::open(&audio_path)
-            .map_err(|e| format!("Failed to open audio file: {}", e))?;
+    /// Drop the sink's fade, returning the gain it had reached
+    fn cancel(&mut self, sink: &Rc<Sink>) -> Option<f32> {
+        let index = self.fades.iter().position(|fade| Rc::ptr_eq(&fade.sink, sink))?;
+        Some(self.fades.remove(index).envelope.gain_at(Instant::now()))
+    }
 
-        let source = Decoder::new(BufReader::new(file))
-            .map_err(|e| format!("Failed to decode audio file: {}", e))?
-            .repeat_infinite();
+    /// Move every fade to where it should be at `now`, dropping finished ones
+    fn step(&mut self, now: Instant) {
+        self.fades.retain(|fade| {
+            fade.sink.set_volume(fade.level * fade.envelope.gain_at(now));
+            let finished = fade.envelope.finished_at(now);
+            if finished && fade.stop_at_end {
+                fade.sink.stop();
+            }
+            !finished
+        });
+    }
+}
 
-        sink.set_volume(volume);
-        sink.append(source);
+/// A mixer layer and the sink playing it
+struct Layer {
+    settings: MixLayer,
+    sink: Rc<Sink>,
+}
+
+/// The output stream, the sinks and everything that drives them. rodio's
+/// OutputStream must stay on the thread that opened it, so a Mixer is only
+/// ever touched on the audio thread; see `AudioPlayer::run`.
+pub struct Mixer {
+    layers: Vec<Layer>,
+    master_volume: f32,
+    fades: FadeSettings,
+    fader: Fader,
+    timer: Option<SleepTimer>,
+    // Shared by every layer; opened with the first and kept through fade-outs
+    // until release_if_idle finds nothing left playing
+    stream: Option<(OutputStream, OutputStreamHandle)>,
+    library: SoundLibrary,
+}
+
+impl Mixer {
+    fn new() -> Self {
+        Self {
+            layers: Vec::new(),
+            master_volume: 1.0,
+            fades: FadeSettings::default(),
+            fader: Fader::default(),
+            timer: None,
+            stream: None,
+            library: SoundLibrary::default(),
+        }
+    }
+
+    /// Point the mixer at the bundled sounds once the app knows its resource dir
+    pub fn set_library(&mut self, library: SoundLibrary) {
+        self.library = library;
+    }
+
+    pub fn sounds(&self) -> Vec<SoundInfo> {
+        self.library.list()
+    }
+
+    pub fn fade_settings(&self) -> FadeSettings {
+        self.fades
+    }
 
-        self.sink = Some(sink);
-        self._stream = Some(stream);
+    pub fn set_fade_settings(&mut self, fades: FadeSettings) {
+        self.fades = fades;
+    }
+
+    /// Loop one catalog track on its own, crossfading from whatever was
+    /// playing. `volume` becomes the master volume so `set_volume` keeps
+    /// controlling it.
+    pub fn play_sound(&mut self, sound: &Sound, volume: f32) -> Result<(), String> {
+        let crossfade = self.fades.crossfade();
+        if self.layers.len() == 1 && self.layers[0].settings.sound_id == sound.id {
+            self.set_volume(volume);
+            return Ok(());
+        }
+        let fade_in = if self.layers.is_empty() { self.fades.fade_in() } else { crossfade };
+        self.fade_out_all(crossfade);
+        self.master_volume = volume;
+        self.start_layer(sound, 1.0, fade_in)
+    }
+
+    /// Start looping `sound` alongside the other layers; a sound that is
+    /// already playing just takes the new volume
+    pub fn add_layer(&mut self, sound: &Sound, volume: f32) -> Result<(), String> {
+        if self.set_layer_volume(sound.id, volume) {
+            return Ok(());
+        }
+        self.start_layer(sound, volume, self.fades.fade_in())
+    }
+
+    /// Returns whether the sound was playing
+    pub fn remove_layer(&mut self, sound_id: &str) -> bool {
+        let Some(index) = self.layer_index(sound_id) else {
+            return false;
+        };
+        let layer = self.layers.remove(index);
+        let level = layer.settings.effective_volume(self.master_volume);
+        self.fader.fade_out(layer.sink, level, self.fades.fade_out());
+        if self.layers.is_empty() {
+            self.timer = None;
+        }
+        true
+    }
+
+    /// Returns whether the sound was playing
+    pub fn set_layer_volume(&mut self, sound_id: &str, volume: f32) -> bool {
+        self.update_layer(sound_id, |layer| layer.volume = volume)
+    }
 
+    /// Returns whether the sound was playing
+    pub fn set_layer_muted(&mut self, sound_id: &str, muted: bool) -> bool {
+        self.update_layer(sound_id, |layer| layer.muted = muted)
+    }
+
+    /// Crossfade to a saved mix. Layers whose sound is no longer in the
+    /// catalog are skipped; the first sound that fails to play stops the
+    /// whole mix.
+    pub fn load_mix(&mut self, master_volume: f32, layers: &[MixLayer]) -> Result<(), String> {
+        let crossfade = self.fades.crossfade();
+        let fade_in = if self.layers.is_empty() { self.fades.fade_in() } else { crossfade };
+        self.fade_out_all(crossfade);
+        self.master_volume = master_volume;
+        for layer in layers {
+            let Some(sound) = sounds::find(&layer.sound_id) else {
+                continue;
+            };
+            if let Err(e) = self.start_layer(sound, layer.volume, fade_in) {
+                self.fade_out_all(Duration::ZERO);
+                return Err(e);
+            }
+            self.set_layer_muted(sound.id, layer.muted);
+        }
         Ok(())
     }
 
+    pub fn state(&self) -> MixerState {
+        MixerState {
+            master_volume: self.master_volume,
+            layers: self.layers.iter().map(|layer| layer.settings.clone()).collect(),
+        }
+    }
+
+    /// Fade every layer out; this also cancels the timer
     pub fn stop(&mut self) {
-        if let Some(sink) = &self.sink {
-            sink.stop();
+        self.timer = None;
+        self.fade_out_all(self.fades.fade_out());
+    }
+
+    /// Silence everything at once, for when the app is quitting
+    pub fn stop_now(&mut self) {
+        self.timer = None;
+        self.fade_out_all(Duration::ZERO);
+        self.stream = None;
+    }
+
+    /// Stop whatever is playing when `timer` expires, even if the sounds
+    /// change in the meantime
+    pub fn set_timer(&mut self, timer: SleepTimer) -> Result<(), String> {
+        if self.layers.is_empty() {
+            return Err("Nothing is playing".to_string());
         }
-        self.sink = None;
-        self._stream = None;
+        self.timer = Some(timer);
+        Ok(())
+    }
+
+    pub fn cancel_timer(&mut self) -> Option<SleepTimer> {
+        self.timer.take()
+    }
+
+    /// Stop playback if the timer is due; returns whether it was
+    pub fn expire_timer(&mut self, now: DateTime<Local>) -> bool {
+        match self.timer {
+            Some(timer) if timer.is_due(now) => {
+                let fade_out = if timer.fade_out { self.fades.fade_out() } else { Duration::ZERO };
+                self.timer = None;
+                self.fade_out_all(fade_out);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn status(&self, now: DateTime<Local>) -> PlaybackStatus {
+        PlaybackStatus::new(self.state(), self.timer, now)
     }
 
-    pub fn set_volume(&self, volume: f32) {
-        if let Some(sink) = &self.sink {
-            sink.set_volume(volume);
+    /// Set the master volume, which scales every layer
+    pub fn set_volume(&mut self, volume: f32) {
+        self.master_volume = volume;
+        for index in 0..self.layers.len() {
+            self.apply_volume(index);
         }
     }
 
     pub fn is_playing(&self) -> bool {
-        self.sink.is_some()
+        !self.layers.is_empty()
+    }
+
+    fn start_layer(&mut self, sound: &Sound, volume: f32, fade_in: Duration) -> Result<(), String> {
+        let source = self.open(sound)?;
+        let settings = MixLayer::new(sound.id, volume);
+        let sink = Sink::try_new(self.output()?)
+            .map_err(|e| format!("Failed to create sink: {}", e))?;
+        let sink = Rc::new(sink);
+        self.fader.fade_in(&sink, settings.effective_volume(self.master_volume), fade_in);
+        sink.append(source);
+
+        self.layers.push(Layer { settings, sink });
+        Ok(())
+    }
+
+    fn fade_out_all(&mut self, duration: Duration) {
+        for layer in std::mem::take(&mut self.layers) {
+            let level = layer.settings.effective_volume(self.master_volume);
+            self.fader.fade_out(layer.sink, level, duration);
+        }
+    }
+
+    fn apply_volume(&mut self, index: usize) {
+        let layer = &self.layers[index];
+        let level = layer.settings.effective_volume(self.master_volume);
+        if !self.fader.set_level(&layer.sink, level) {
+            layer.sink.set_volume(level);
+        }
+    }
+
+    /// An endless source for `sound`: a looped file or generated noise
+    fn open(&self, sound: &Sound) -> Result<Box<dyn Source<Item = f32> + Send>, String> {
+        let path = match sound.kind {
+            SoundKind::Noise(color) => return Ok(Box::new(Noise::new(color))),
+            SoundKind::File(file) => self.library.dir().join(file),
+        };
+        let file = File::open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+        let source = Decoder::new(BufReader::new(file))
+            .map_err(|e| format!("Failed to decode audio file: {}", e))?
+            .repeat_infinite()
+            .convert_samples();
+        Ok(Box::new(source))
+    }
+
+    fn output(&mut self) -> Result<&OutputStreamHandle, String> {
+        // A stream left open for fade-outs that have since finished is reused
+        let stream = match self.stream.take() {
+            Some(stream) => stream,
+            None => OutputStream::try_default()
+                .map_err(|e| format!("Failed to create audio stream: {}", e))?,
+        };
+        Ok(&self.stream.insert(stream).1)
+    }
+
+    /// Close the audio device once nothing, not even a fade-out, is left
+    /// playing; the audio thread checks after every command and fade step
+    fn release_if_idle(&mut self) {
+        if self.layers.is_empty() && self.fader.is_idle() {
+            self.stream = None;
+        }
+    }
+
+    fn layer_index(&self, sound_id: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.settings.sound_id == sound_id)
+    }
+
+    fn update_layer(&mut self, sound_id: &str, update: impl FnOnce(&mut MixLayer)) -> bool {
+        let Some(index) = self.layer_index(sound_id) else {
+            return false;
+        };
+        update(&mut self.layers[index].settings);
+        self.apply_volume(index);
+        true
+    }
+}
+
+type Command = Box<dyn FnOnce(&mut Mixer) + Send>;
+
+/// Handle to the audio thread, which owns the Mixer and with it the output
+/// stream and every sink. Commands run there one at a time, and fades are
+/// stepped between them.
+pub struct AudioPlayer {
+    commands: Sender<Command>,
+    // Mirrors whether the mixer has a sleep timer, updated after every command
+    timer_armed: Arc<AtomicBool>,
+}
+
+impl AudioPlayer {
+    pub fn new() -> Self {
+        let (commands, receiver) = mpsc::channel();
+        let timer_armed = Arc::new(AtomicBool::new(false));
+        let armed = Arc::clone(&timer_armed);
+        thread::spawn(move || run(receiver, &armed));
+        Self { commands, timer_armed }
+    }
+
+    /// Whether a sleep timer is set, without a round trip to the audio thread
+    pub fn has_timer(&self) -> bool {
+        self.timer_armed.load(Ordering::SeqCst)
+    }
+
+    /// Run `command` on the audio thread and wait for its result
+    pub fn run<R, F>(&self, command: F) -> Result<R, String>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Mixer) -> R + Send + 'static,
+    {
+        let (reply, result) = mpsc::channel();
+        self.commands
+            .send(Box::new(move |mixer: &mut Mixer| {
+                let _ = reply.send(command(mixer));
+            }))
+            .map_err(|_| "Audio thread has stopped".to_string())?;
+        result.recv().map_err(|_| "Audio thread has stopped".to_string())
+    }
+}
+
+/// The audio thread: wait for commands, waking every FADE_STEP while a fade
+/// is in progress, and close the device as soon as nothing is playing.
+/// Ends once the AudioPlayer is gone.
+fn run(commands: Receiver<Command>, timer_armed: &AtomicBool) {
+    let mut mixer = Mixer::new();
+    loop {
+        let command = if mixer.fader.is_idle() {
+            commands.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            commands.recv_timeout(FADE_STEP)
+        };
+        match command {
+            Ok(command) => command(&mut mixer),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        mixer.fader.step(Instant::now());
+        mixer.release_if_idle();
+        timer_armed.store(mixer.timer.is_some(), Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    const SEGMENT: usize = 4096;
+
+    /// In-place radix-2 FFT over (re, im) pairs
+    fn fft(data: &mut [(f64, f64)]) {
+        let n = data.len();
+        let mut j = 0;
+        for i in 1..n {
+            let mut bit = n >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j |= bit;
+            if i < j {
+                data.swap(i, j);
+            }
+        }
+        let mut len = 2;
+        while len <= n {
+            let angle = -2.0 * PI / len as f64;
+            for start in (0..n).step_by(len) {
+                for k in 0..len / 2 {
+                    let (sin, cos) = (angle * k as f64).sin_cos();
+                    let (re, im) = data[start + k + len / 2];
+                    let twiddled = (re * cos - im * sin, re * sin + im * cos);
+                    let even = data[start + k];
+                    data[start + k] = (even.0 + twiddled.0, even.1 + twiddled.1);
+                    data[start + k + len / 2] = (even.0 - twiddled.0, even.1 - twiddled.1);
+                }
+            }
+            len <<= 1;
+        }
+    }
+
+    /// Least-squares slope of log10(power) against log10(frequency) between
+    /// `low` and `high` Hz, from Hann-windowed periodograms averaged over
+    /// half-octave bands. White noise gives 0, pink -1 and brown -2.
+    fn spectral_slope(samples: &[f32], low: f64, high: f64) -> f64 {
+        let mut power = vec![0.0; SEGMENT / 2];
+        for segment in samples.chunks_exact(SEGMENT) {
+            let mut data: Vec<(f64, f64)> = segment.iter().enumerate()
+                .map(|(i, &s)| {
+                    let hann = 0.5 - 0.5 * (2.0 * PI * i as f64 / SEGMENT as f64).cos();
+                    (s as f64 * hann, 0.0)
+                })
+                .collect();
+            fft(&mut data);
+            for (bin, (re, im)) in data.iter().take(SEGMENT / 2).enumerate() {
+                power[bin] += re * re + im * im;
+            }
+        }
+
+        let bin_width = NOISE_SAMPLE_RATE as f64 / SEGMENT as f64;
+        let mut points = Vec::new();
+        let mut band = low;
+        while band * 2f64.sqrt() <= high {
+            let (start, end) = ((band / bin_width) as usize, (band * 2f64.sqrt() / bin_width) as usize);
+            let mean = power[start..end].iter().sum::<f64>() / (end - start) as f64;
+            points.push(((band * 2f64.powf(0.25)).log10(), mean.log10()));
+            band *= 2f64.sqrt();
+        }
+
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+        let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+        covariance / variance
+    }
+
+    fn generate(color: NoiseColor) -> Vec<f32> {
+        Noise::with_seed(color, 12345).take(SEGMENT * 32).collect()
+    }
+
+    #[test]
+    fn test_noise_spectral_slopes() {
+        for (color, expected) in [(NoiseColor::White, 0.0), (NoiseColor::Pink, -1.0), (NoiseColor::Brown, -2.0)] {
+            let slope = spectral_slope(&generate(color), 200.0, 8000.0);
+            assert!((slope - expected).abs() < 0.25, "{:?} noise slope {:.2}, expected {}", color, slope, expected);
+        }
+    }
+
+    #[test]
+    fn test_noise_stays_in_range_and_centered() {
+        for color in [NoiseColor::White, NoiseColor::Pink, NoiseColor::Brown] {
+            let samples = generate(color);
+            assert!(samples.iter().all(|s| (-1.0..=1.0).contains(s)));
+            let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / samples.len() as f64;
+            let rms = (samples.iter().map(|&s| (s as f64).powi(2)).sum::<f64>() / samples.len() as f64).sqrt();
+            assert!(mean.abs() < 0.05, "{:?} noise mean {:.3}", color, mean);
+            assert!((0.1..0.5).contains(&rms), "{:?} noise rms {:.3}", color, rms);
+        }
+    }
+
+    #[test]
+    fn test_envelope_ramps_linearly() {
+        let envelope = Envelope::new(1.0, 0.0, Duration::from_millis(1000));
+        let at = |ms| envelope.started + Duration::from_millis(ms);
+        assert_eq!(envelope.gain_at(at(0)), 1.0);
+        assert!((envelope.gain_at(at(250)) - 0.75).abs() < 1e-6);
+        assert!(!envelope.finished_at(at(999)));
+        assert_eq!(envelope.gain_at(at(5000)), 0.0);
+        assert!(envelope.finished_at(at(1000)));
+    }
+
+    #[test]
+    fn test_fader_reaches_targets_and_goes_idle() {
+        let mut fader = Fader::default();
+        let (rising, _rising_out) = Sink::new_idle();
+        let (falling, _falling_out) = Sink::new_idle();
+        let (rising, falling) = (Rc::new(rising), Rc::new(falling));
+
+        fader.fade_in(&rising, 0.6, Duration::from_millis(100));
+        assert_eq!(rising.volume(), 0.0);
+        fader.fade_out(falling.clone(), 0.8, Duration::from_millis(100));
+        assert!(fader.set_level(&rising, 0.5));
+        assert!(!fader.is_idle());
+
+        fader.step(Instant::now() + Duration::from_millis(50));
+        assert!((rising.volume() - 0.25).abs() < 0.02, "rising at {}", rising.volume());
+        assert!(!fader.is_idle());
+
+        fader.step(Instant::now() + Duration::from_millis(400));
+        assert!(fader.is_idle());
+        assert_eq!(rising.volume(), 0.5);
+        assert_eq!(falling.volume(), 0.0);
+        assert!(!fader.set_level(&rising, 0.3));
+    }
+
+    #[test]
+    fn test_zero_length_fades_apply_at_once() {
+        let mut fader = Fader::default();
+        let (sink, _out) = Sink::new_idle();
+        let sink = Rc::new(sink);
+        fader.fade_in(&sink, 0.4, Duration::ZERO);
+        assert_eq!(sink.volume(), 0.4);
+        assert!(fader.is_idle());
+    }
+
+    #[test]
+    fn test_commands_run_on_the_audio_thread() {
+        let player = AudioPlayer::new();
+        let caller = thread::current().id();
+        let (audio_thread, fades) = player.run(|mixer| (thread::current().id(), mixer.fade_settings())).unwrap();
+        assert_ne!(audio_thread, caller);
+        assert_eq!(fades, FadeSettings::default());
+
+        // Commands from any thread land on the same one
+        let player = std::sync::Arc::new(player);
+        let other = std::sync::Arc::clone(&player);
+        let from_other = thread::spawn(move || other.run(|_| thread::current().id()).unwrap()).join().unwrap();
+        assert_eq!(from_other, audio_thread);
+    }
+
+    #[test]
+    fn test_has_timer_follows_the_mixer() {
+        let player = AudioPlayer::new();
+        assert!(!player.has_timer());
+
+        let timer = SleepTimer::after(5, true, Local::now()).unwrap();
+        player.run(move |mixer| mixer.timer = Some(timer)).unwrap();
+        assert!(player.has_timer());
+
+        player.run(|mixer| mixer.cancel_timer()).unwrap();
+        assert!(!player.has_timer());
+    }
+
+    #[test]
+    fn test_noise_source_is_endless_mono() {
+        let noise = Noise::with_seed(NoiseColor::Pink, 1);
+        assert_eq!((noise.channels(), noise.sample_rate()), (1, NOISE_SAMPLE_RATE));
+        assert_eq!(noise.total_duration(), None);
+        assert_ne!(Noise::with_seed(NoiseColor::White, 1).take(8).collect::<Vec<_>>(),
+                   Noise::with_seed(NoiseColor::White, 2).take(8).collect::<Vec<_>>());
     }
 }
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Local};
+
+use crate::db::{DailySummary, Database};
+use crate::emotion_analysis::{summarize_day, CheckinBlend};
+
+/// Recompute and store the summary of one local date ("YYYY-MM-DD") from its
+/// raw records and check-ins
+pub fn refresh_day(db: &Database, date: &str, checkin_weight: f64, now: DateTime<Local>) -> rusqlite::Result<DailySummary> {
+    let start = format!("{} 00:00:00", date);
+    let end = format!("{} 23:59:59", date);
+    let records = db.get_records_by_date_range(&start, &end)?;
+    let checkins = db.get_checkins_by_date_range(&start, &end)?;
+
+    let blend = CheckinBlend { checkins: &checkins, weight: checkin_weight };
+    let summary = summarize_day(date, records, &blend, now.timestamp());
+    db.upsert_daily_summary(&summary)?;
+    Ok(summary)
+}
+
+/// Summarize past days that have records but no summary, such as records
+/// from before the table existed, and recompute past days that received
+/// records or check-ins after they were summarized. Run it before retention
+/// compacts raw records, since the summary is all that is left of those days
+/// afterwards.
+pub fn backfill(db: &Database, checkin_weight: f64, now: DateTime<Local>) -> rusqlite::Result<usize> {
+    let today = now.format("%Y-%m-%d").to_string();
+    let dates = db.get_stale_summary_dates(&today)?;
+    for date in &dates {
+        refresh_day(db, date, checkin_weight, now)?;
+    }
+    Ok(dates.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{EmotionRecord, MoodCheckin};
+    use crate::retention::rollup_records;
+    use chrono::TimeZone;
+    use std::path::PathBuf;
+
+    fn record(at: DateTime<Local>, emotion: &str) -> EmotionRecord {
+        EmotionRecord {
+            id: None,
+            timestamp: at.timestamp(),
+            datetime: at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            fer_emotion: emotion.to_string(),
+            fer_confidence: 0.8,
+            deepface_emotion: None,
+            deepface_confidence: None,
+            mapped_emotion: emotion.to_string(),
+            work_minutes: 0.0,
+            is_away: false,
+            has_face: true,
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 1, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_refresh_day_counts_and_replaces() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.init().unwrap();
+        db.insert_record(&record(at(1, 9, 0), "happy")).unwrap();
+        db.insert_record(&record(at(1, 9, 1), "worried")).unwrap();
+        db.insert_checkin(&MoodCheckin::new("calm".to_string(), 8, None, at(1, 12, 0))).unwrap();
+
+        let first = refresh_day(&db, "2025-01-01", 0.3, at(1, 12, 0)).unwrap();
+        assert_eq!((first.happy_count, first.worried_count, first.total_records), (1, 1, 2));
+        assert_eq!(first.checkin_count, 1);
+
+        db.insert_record(&record(at(1, 9, 2), "happy")).unwrap();
+        refresh_day(&db, "2025-01-01", 0.3, at(1, 12, 5)).unwrap();
+        let stored = db.get_daily_summaries("2025-01-01", "2025-01-01").unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].happy_count, 2);
+        assert_eq!(stored[0].updated_at, at(1, 12, 5).timestamp());
+    }
+
+    #[test]
+    fn test_backfill_skips_today_and_summarized_days() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.init().unwrap();
+        for r in [record(at(1, 9, 0), "calm"), record(at(2, 9, 0), "tired"), record(at(3, 9, 0), "happy")] {
+            db.insert_record(&r).unwrap();
+        }
+        refresh_day(&db, "2025-01-01", 0.3, at(1, 23, 0)).unwrap();
+
+        assert_eq!(backfill(&db, 0.3, at(3, 10, 0)).unwrap(), 1);
+        let dates: Vec<String> = db.get_daily_summaries("2025-01-01", "2025-01-31").unwrap()
+            .into_iter()
+            .map(|s| s.date)
+            .collect();
+        assert_eq!(dates, ["2025-01-01", "2025-01-02"]);
+        assert_eq!(backfill(&db, 0.3, at(3, 10, 0)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_backfill_recomputes_days_with_late_data() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.init().unwrap();
+        db.insert_record(&record(at(1, 9, 0), "calm")).unwrap();
+        assert_eq!(backfill(&db, 0.3, at(2, 10, 0)).unwrap(), 1);
+
+        // A record and a check-in for yesterday arrive after it was summarized
+        db.insert_record(&record(at(1, 9, 5), "happy")).unwrap();
+        assert_eq!(backfill(&db, 0.3, at(2, 11, 0)).unwrap(), 1);
+        let summary = &db.get_daily_summaries("2025-01-01", "2025-01-01").unwrap()[0];
+        assert_eq!((summary.total_records, summary.happy_count), (2, 1));
+        assert_eq!(summary.updated_at, at(2, 11, 0).timestamp());
+
+        db.insert_checkin(&MoodCheckin::new("tired".to_string(), 3, None, at(1, 20, 0))).unwrap();
+        assert_eq!(backfill(&db, 0.3, at(2, 12, 0)).unwrap(), 1);
+        assert_eq!(db.get_daily_summaries("2025-01-01", "2025-01-01").unwrap()[0].checkin_count, 1);
+        assert_eq!(backfill(&db, 0.3, at(2, 12, 5)).unwrap(), 0);
+
+        // Compaction removes raw records but must not trigger a recompute
+        let rollups = rollup_records(&db.get_records_in("2025-01-01 00:00:00", "2025-01-02 00:00:00").unwrap());
+        db.replace_records_with_rollups("2025-01-01 00:00:00", "2025-01-02 00:00:00", &rollups).unwrap();
+        assert_eq!(backfill(&db, 0.3, at(2, 13, 0)).unwrap(), 0);
+
+        // Nor may late data on a compacted day wipe its summary
+        db.insert_checkin(&MoodCheckin::new("calm".to_string(), 7, None, at(1, 21, 0))).unwrap();
+        db.insert_record(&record(at(1, 22, 0), "worried")).unwrap();
+        assert_eq!(backfill(&db, 0.3, at(2, 14, 0)).unwrap(), 0);
+        let summary = &db.get_daily_summaries("2025-01-01", "2025-01-01").unwrap()[0];
+        assert_eq!((summary.total_records, summary.happy_count, summary.calm_count), (2, 1, 1));
+        assert_eq!(summary.checkin_count, 1);
+    }
+}
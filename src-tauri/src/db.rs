@@ -1,10 +1,17 @@
-use rusqlite::{Connection, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use rusqlite::{Connection, Result, Row};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::sounds::MixLayer;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct EmotionRecord {
+    #[ts(type = "number | null")]
     pub id: Option<i64>,
+    #[ts(type = "number")]
     pub timestamp: i64,
     pub datetime: String,
     pub fer_emotion: String,
@@ -17,19 +24,249 @@ pub struct EmotionRecord {
     pub has_face: bool,
 }
 
-pub struct Database {
-    conn: Connection,
+/// Map a row selected as `id, timestamp, datetime, fer_emotion, fer_confidence,
+/// deepface_emotion, deepface_confidence, mapped_emotion, work_minutes,
+/// is_away, has_face`
+fn row_to_record(row: &Row) -> Result<EmotionRecord> {
+    Ok(EmotionRecord {
+        id: Some(row.get(0)?),
+        timestamp: row.get(1)?,
+        datetime: row.get(2)?,
+        fer_emotion: row.get(3)?,
+        fer_confidence: row.get(4)?,
+        deepface_emotion: row.get(5)?,
+        deepface_confidence: row.get(6)?,
+        mapped_emotion: row.get(7)?,
+        work_minutes: row.get(8)?,
+        is_away: row.get::<_, i32>(9)? != 0,
+        has_face: row.get::<_, i32>(10)? != 0,
+    })
 }
 
-impl Database {
-    pub fn new(db_path: PathBuf) -> Result<Self> {
-        let conn = Connection::open(db_path)?;
-        Ok(Database { conn })
+/// The emotion vocabulary every table accepts (mirrors the CHECK constraints)
+pub const EMOTIONS: [&str; 4] = ["happy", "calm", "worried", "tired"];
+
+/// Where a frontend-sourced entry came from
+pub const ENTRY_SOURCES: [&str; 2] = ["manual", "import"];
+
+/// An emotion entered by the user or imported, stored in `emotion_entries`
+/// next to the detected `emotion_records`
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EmotionEntry {
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub id: Option<i64>,
+    /// Filled in from `datetime` by `validate`
+    #[serde(default)]
+    #[ts(type = "number")]
+    pub timestamp: i64,
+    /// Local "YYYY-MM-DD HH:MM:SS"; RFC 3339 is accepted on input
+    pub datetime: String,
+    pub emotion: String,
+    #[serde(default = "default_entry_confidence")]
+    pub confidence: f64,
+    #[serde(default = "default_entry_source")]
+    pub source: String,
+}
+
+fn default_entry_confidence() -> f64 {
+    1.0
+}
+
+fn default_entry_source() -> String {
+    "manual".to_string()
+}
+
+impl EmotionEntry {
+    /// Check the entry against the schema's vocabulary and ranges, and
+    /// normalize `datetime` to local time with a matching `timestamp`
+    pub fn validate(&mut self) -> std::result::Result<(), String> {
+        if !EMOTIONS.contains(&self.emotion.as_str()) {
+            return Err(format!("Unknown emotion '{}', expected one of {}", self.emotion, EMOTIONS.join(", ")));
+        }
+        if !ENTRY_SOURCES.contains(&self.source.as_str()) {
+            return Err(format!("Unknown source '{}', expected one of {}", self.source, ENTRY_SOURCES.join(", ")));
+        }
+        if !(0.0..=1.0).contains(&self.confidence) {
+            return Err(format!("Confidence must be between 0 and 1, got {}", self.confidence));
+        }
+
+        let local = parse_local_datetime(&self.datetime)
+            .ok_or_else(|| format!("Invalid datetime '{}'", self.datetime))?;
+        self.timestamp = local.timestamp();
+        self.datetime = local.format("%Y-%m-%d %H:%M:%S").to_string();
+        Ok(())
     }
+}
 
-    pub fn init(&self) -> Result<()> {
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS emotion_records (
+/// A self-reported mood, stored in `mood_checkins`
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MoodCheckin {
+    #[ts(type = "number | null")]
+    pub id: Option<i64>,
+    #[ts(type = "number")]
+    pub timestamp: i64,
+    pub datetime: String,
+    pub emotion: String,
+    /// How strongly the emotion is felt, 1-10
+    pub intensity: u8,
+    pub note: Option<String>,
+}
+
+pub const MAX_NOTE_LENGTH: usize = 500;
+
+impl MoodCheckin {
+    pub fn new(emotion: String, intensity: u8, note: Option<String>, now: DateTime<Local>) -> Self {
+        MoodCheckin {
+            id: None,
+            timestamp: now.timestamp(),
+            datetime: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+            emotion,
+            intensity,
+            // An empty note is no note
+            note: note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+        }
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if !EMOTIONS.contains(&self.emotion.as_str()) {
+            return Err(format!("Unknown emotion '{}', expected one of {}", self.emotion, EMOTIONS.join(", ")));
+        }
+        if !(1..=10).contains(&self.intensity) {
+            return Err(format!("Intensity must be between 1 and 10, got {}", self.intensity));
+        }
+        if self.note.as_ref().map_or(0, |n| n.chars().count()) > MAX_NOTE_LENGTH {
+            return Err(format!("Note is longer than {} characters", MAX_NOTE_LENGTH));
+        }
+        Ok(())
+    }
+}
+
+/// Half an hour of compacted `emotion_records`, kept after the raw rows
+/// have aged out (see retention.rs)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EmotionRollup {
+    /// Start of the half hour
+    #[ts(type = "number")]
+    pub timestamp: i64,
+    /// Start of the half hour, local "YYYY-MM-DD HH:MM:SS"
+    pub datetime: String,
+    #[ts(type = "number")]
+    pub happy_count: i64,
+    #[ts(type = "number")]
+    pub calm_count: i64,
+    #[ts(type = "number")]
+    pub worried_count: i64,
+    #[ts(type = "number")]
+    pub tired_count: i64,
+    /// Mean fer_confidence of the compacted records
+    pub mean_confidence: f64,
+    /// Time spent in front of the camera, from consecutive focused records
+    pub focus_minutes: f64,
+}
+
+impl EmotionRollup {
+    pub fn sample_count(&self) -> i64 {
+        self.happy_count + self.calm_count + self.worried_count + self.tired_count
+    }
+}
+
+/// One day's analyses, kept in `daily_summaries` so past days can be shown
+/// without their raw records
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DailySummary {
+    /// Local date, "YYYY-MM-DD"
+    pub date: String,
+    pub emotion_index: f64,
+    pub stress_level: f64,
+    #[ts(type = "number")]
+    pub total_records: i64,
+    #[ts(type = "number")]
+    pub valid_records: i64,
+    #[ts(type = "number")]
+    pub focus_sessions: i64,
+    pub focus_minutes: f64,
+    #[ts(type = "number")]
+    pub happy_count: i64,
+    #[ts(type = "number")]
+    pub calm_count: i64,
+    #[ts(type = "number")]
+    pub worried_count: i64,
+    #[ts(type = "number")]
+    pub tired_count: i64,
+    #[ts(type = "number")]
+    pub checkin_count: i64,
+    /// When the summary was last recomputed
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+const MAX_MIX_NAME_LENGTH: usize = 40;
+
+/// A named set of mixer layers, stored in `sound_mixes`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SoundMix {
+    pub name: String,
+    pub master_volume: f32,
+    pub layers: Vec<MixLayer>,
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+impl SoundMix {
+    pub fn new(name: &str, master_volume: f32, layers: Vec<MixLayer>, now: DateTime<Local>) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            master_volume,
+            layers,
+            updated_at: now.timestamp(),
+        }
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.name.is_empty() {
+            return Err("Mix name cannot be empty".to_string());
+        }
+        if self.name.chars().count() > MAX_MIX_NAME_LENGTH {
+            return Err(format!("Mix name is longer than {} characters", MAX_MIX_NAME_LENGTH));
+        }
+        if self.layers.is_empty() {
+            return Err("Mix has no layers".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Parse either our local "YYYY-MM-DD HH:MM:SS" format or an RFC 3339 timestamp
+fn parse_local_datetime(value: &str) -> Option<DateTime<Local>> {
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(value) {
+        return Some(parsed.with_timezone(&Local));
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+}
+
+/// One forward step of the schema. Never edit a migration that has shipped;
+/// add a new one and a fixture for the version it starts from (see db_tests.rs).
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "emotion_records",
+        // IF NOT EXISTS: databases created before versioning already have this table at user_version 0
+        sql: "CREATE TABLE IF NOT EXISTS emotion_records (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 timestamp INTEGER NOT NULL,
                 datetime TEXT NOT NULL,
@@ -46,33 +283,177 @@ impl Database {
                 CHECK (mapped_emotion IN ('happy', 'calm', 'worried', 'tired')),
                 CHECK (is_away IN (0, 1)),
                 CHECK (has_face IN (0, 1))
-            )",
-            [],
-        )?;
+            );
+            CREATE INDEX IF NOT EXISTS idx_timestamp ON emotion_records(timestamp);
+            CREATE INDEX IF NOT EXISTS idx_datetime ON emotion_records(datetime);
+            CREATE INDEX IF NOT EXISTS idx_mapped_emotion ON emotion_records(mapped_emotion);
+            CREATE INDEX IF NOT EXISTS idx_datetime_emotion ON emotion_records(datetime, mapped_emotion);",
+    },
+    Migration {
+        version: 2,
+        description: "emotion_entries and the all_emotions view",
+        sql: "CREATE TABLE emotion_entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                datetime TEXT NOT NULL,
+                emotion TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                source TEXT NOT NULL,
+                CHECK (emotion IN ('happy', 'calm', 'worried', 'tired')),
+                CHECK (confidence >= 0 AND confidence <= 1),
+                CHECK (source IN ('manual', 'import'))
+            );
+            CREATE INDEX idx_entries_timestamp ON emotion_entries(timestamp);
+            CREATE INDEX idx_entries_datetime ON emotion_entries(datetime);
+            CREATE VIEW all_emotions AS
+                SELECT NULL AS id, timestamp, datetime, mapped_emotion AS emotion,
+                       fer_confidence AS confidence, 'detected' AS source
+                FROM emotion_records
+                UNION ALL
+                SELECT id, timestamp, datetime, emotion, confidence, source
+                FROM emotion_entries;",
+    },
+    Migration {
+        version: 3,
+        description: "mood_checkins",
+        sql: "CREATE TABLE mood_checkins (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                datetime TEXT NOT NULL,
+                emotion TEXT NOT NULL,
+                intensity INTEGER NOT NULL,
+                note TEXT,
+                CHECK (emotion IN ('happy', 'calm', 'worried', 'tired')),
+                CHECK (intensity BETWEEN 1 AND 10)
+            );
+            CREATE INDEX idx_checkins_datetime ON mood_checkins(datetime);",
+    },
+    Migration {
+        version: 4,
+        description: "emotion_rollups",
+        sql: "CREATE TABLE emotion_rollups (
+                datetime TEXT PRIMARY KEY,
+                timestamp INTEGER NOT NULL,
+                happy_count INTEGER NOT NULL,
+                calm_count INTEGER NOT NULL,
+                worried_count INTEGER NOT NULL,
+                tired_count INTEGER NOT NULL,
+                mean_confidence REAL NOT NULL,
+                focus_minutes REAL NOT NULL,
+                CHECK (happy_count >= 0 AND calm_count >= 0 AND worried_count >= 0 AND tired_count >= 0),
+                CHECK (mean_confidence >= 0 AND mean_confidence <= 1),
+                CHECK (focus_minutes >= 0)
+            );
+            CREATE INDEX idx_rollups_timestamp ON emotion_rollups(timestamp);",
+    },
+    Migration {
+        version: 5,
+        description: "daily_summaries",
+        sql: "CREATE TABLE daily_summaries (
+                date TEXT PRIMARY KEY,
+                emotion_index REAL NOT NULL,
+                stress_level REAL NOT NULL,
+                total_records INTEGER NOT NULL,
+                valid_records INTEGER NOT NULL,
+                focus_sessions INTEGER NOT NULL,
+                focus_minutes REAL NOT NULL,
+                happy_count INTEGER NOT NULL,
+                calm_count INTEGER NOT NULL,
+                worried_count INTEGER NOT NULL,
+                tired_count INTEGER NOT NULL,
+                checkin_count INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                CHECK (emotion_index >= 1 AND emotion_index <= 10),
+                CHECK (stress_level >= 0 AND stress_level <= 100)
+            );",
+    },
+    Migration {
+        version: 6,
+        description: "sound_mixes and sound_mix_layers",
+        sql: "CREATE TABLE sound_mixes (
+                name TEXT PRIMARY KEY,
+                master_volume REAL NOT NULL,
+                updated_at INTEGER NOT NULL,
+                CHECK (master_volume >= 0 AND master_volume <= 1)
+            );
+            CREATE TABLE sound_mix_layers (
+                mix_name TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                sound_id TEXT NOT NULL,
+                volume REAL NOT NULL,
+                muted INTEGER NOT NULL,
+                PRIMARY KEY (mix_name, position),
+                CHECK (volume >= 0 AND volume <= 1),
+                CHECK (muted IN (0, 1))
+            );",
+    },
+];
 
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_timestamp ON emotion_records(timestamp)",
-            [],
-        )?;
+/// The schema version this build writes
+pub const SCHEMA_VERSION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].version;
 
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_datetime ON emotion_records(datetime)",
-            [],
-        )?;
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The file was written by a newer MoodPulse; we leave it untouched
+    TooNew { found: i64, supported: i64 },
+    /// Migration to `version` failed and was rolled back
+    Sql { version: i64, source: rusqlite::Error },
+}
 
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_mapped_emotion ON emotion_records(mapped_emotion)",
-            [],
-        )?;
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::TooNew { found, supported } => write!(
+                f,
+                "Database schema v{} is newer than this version of MoodPulse supports (v{}); please update the app",
+                found, supported
+            ),
+            MigrationError::Sql { version, source } => {
+                write!(f, "Database migration to v{} failed: {}", version, source)
+            }
+        }
+    }
+}
 
-        self.conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_datetime_emotion ON emotion_records(datetime, mapped_emotion)",
-            [],
-        )?;
+impl std::error::Error for MigrationError {}
+
+pub struct Database {
+    conn: Connection,
+}
+
+impl Database {
+    pub fn new(db_path: PathBuf) -> Result<Self> {
+        let conn = Connection::open(db_path)?;
+        Ok(Database { conn })
+    }
+
+    /// Bring the schema up to `SCHEMA_VERSION`, one transaction per migration
+    pub fn init(&self) -> std::result::Result<(), MigrationError> {
+        let current = self.schema_version().map_err(|source| MigrationError::Sql { version: 0, source })?;
+        if current > SCHEMA_VERSION {
+            return Err(MigrationError::TooNew { found: current, supported: SCHEMA_VERSION });
+        }
+
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+            self.apply(migration)
+                .map_err(|source| MigrationError::Sql { version: migration.version, source })?;
+            println!("Database migrated to v{}: {}", migration.version, migration.description);
+        }
 
         Ok(())
     }
 
+    pub fn schema_version(&self) -> Result<i64> {
+        self.conn.pragma_query_value(None, "user_version", |row| row.get(0))
+    }
+
+    fn apply(&self, migration: &Migration) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch(migration.sql)?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()
+    }
+
     pub fn insert_record(&self, record: &EmotionRecord) -> Result<i64> {
         self.conn.execute(
             "INSERT INTO emotion_records (
@@ -109,21 +490,7 @@ impl Database {
              LIMIT ?1"
         )?;
 
-        let records = stmt.query_map([limit], |row| {
-            Ok(EmotionRecord {
-                id: Some(row.get(0)?),
-                timestamp: row.get(1)?,
-                datetime: row.get(2)?,
-                fer_emotion: row.get(3)?,
-                fer_confidence: row.get(4)?,
-                deepface_emotion: row.get(5)?,
-                deepface_confidence: row.get(6)?,
-                mapped_emotion: row.get(7)?,
-                work_minutes: row.get(8)?,
-                is_away: row.get::<_, i32>(9)? != 0,
-                has_face: row.get::<_, i32>(10)? != 0,
-            })
-        })?;
+        let records = stmt.query_map([limit], row_to_record)?;
 
         records.collect()
     }
@@ -138,21 +505,30 @@ impl Database {
              ORDER BY timestamp ASC"
         )?;
 
-        let records = stmt.query_map([start_date, end_date], |row| {
-            Ok(EmotionRecord {
-                id: Some(row.get(0)?),
-                timestamp: row.get(1)?,
-                datetime: row.get(2)?,
-                fer_emotion: row.get(3)?,
-                fer_confidence: row.get(4)?,
-                deepface_emotion: row.get(5)?,
-                deepface_confidence: row.get(6)?,
-                mapped_emotion: row.get(7)?,
-                work_minutes: row.get(8)?,
-                is_away: row.get::<_, i32>(9)? != 0,
-                has_face: row.get::<_, i32>(10)? != 0,
-            })
-        })?;
+        let records = stmt.query_map([start_date, end_date], row_to_record)?;
+
+        records.collect()
+    }
+
+    /// Up to `limit` records in a datetime range that sort after `after`, a
+    /// (timestamp, id) pair, in that order. Paging this way lets a long read
+    /// run in short queries instead of one that holds the connection.
+    pub fn get_records_page(&self, start_date: &str, end_date: &str, after: Option<(i64, i64)>, limit: usize) -> Result<Vec<EmotionRecord>> {
+        let (timestamp, id) = after.unwrap_or((i64::MIN, i64::MIN));
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, datetime, fer_emotion, fer_confidence,
+                    deepface_emotion, deepface_confidence, mapped_emotion,
+                    work_minutes, is_away, has_face
+             FROM emotion_records
+             WHERE datetime BETWEEN ?1 AND ?2 AND (timestamp, id) > (?3, ?4)
+             ORDER BY timestamp ASC, id ASC
+             LIMIT ?5"
+        )?;
+
+        let records = stmt.query_map(
+            rusqlite::params![start_date, end_date, timestamp, id, limit as i64],
+            row_to_record,
+        )?;
 
         records.collect()
     }
@@ -183,22 +559,379 @@ impl Database {
              ORDER BY timestamp ASC"
         )?;
 
-        let records = stmt.query_map([], |row| {
-            Ok(EmotionRecord {
+        let records = stmt.query_map([], row_to_record)?;
+
+        records.collect()
+    }
+
+    /// Datetime of the oldest raw record, if any
+    pub fn oldest_record_datetime(&self) -> Result<Option<String>> {
+        self.conn.query_row("SELECT MIN(datetime) FROM emotion_records", [], |row| row.get(0))
+    }
+
+    /// Raw records with `start <= datetime < end`, the unit retention compacts
+    pub fn get_records_in(&self, start: &str, end: &str) -> Result<Vec<EmotionRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, datetime, fer_emotion, fer_confidence,
+                    deepface_emotion, deepface_confidence, mapped_emotion,
+                    work_minutes, is_away, has_face
+             FROM emotion_records
+             WHERE datetime >= ?1 AND datetime < ?2
+             ORDER BY timestamp ASC"
+        )?;
+
+        let records = stmt.query_map([start, end], row_to_record)?;
+
+        records.collect()
+    }
+
+    /// Store `rollups` and delete the raw records in `[start, end)` they were
+    /// built from, in one transaction. A half hour that already has a rollup
+    /// is merged into it. Returns the number of raw records deleted.
+    pub fn replace_records_with_rollups(&self, start: &str, end: &str, rollups: &[EmotionRollup]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            // SQLite evaluates every SET expression against the old row
+            let mut stmt = tx.prepare(
+                "INSERT INTO emotion_rollups (
+                    datetime, timestamp,
+                    happy_count, calm_count, worried_count, tired_count,
+                    mean_confidence, focus_minutes
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                ON CONFLICT(datetime) DO UPDATE SET
+                    happy_count = happy_count + excluded.happy_count,
+                    calm_count = calm_count + excluded.calm_count,
+                    worried_count = worried_count + excluded.worried_count,
+                    tired_count = tired_count + excluded.tired_count,
+                    mean_confidence = (
+                        mean_confidence * (happy_count + calm_count + worried_count + tired_count)
+                        + excluded.mean_confidence * (excluded.happy_count + excluded.calm_count
+                            + excluded.worried_count + excluded.tired_count)
+                    ) / MAX(1, happy_count + calm_count + worried_count + tired_count
+                        + excluded.happy_count + excluded.calm_count
+                        + excluded.worried_count + excluded.tired_count),
+                    focus_minutes = focus_minutes + excluded.focus_minutes"
+            )?;
+            for rollup in rollups {
+                stmt.execute(rusqlite::params![
+                    rollup.datetime,
+                    rollup.timestamp,
+                    rollup.happy_count,
+                    rollup.calm_count,
+                    rollup.worried_count,
+                    rollup.tired_count,
+                    rollup.mean_confidence,
+                    rollup.focus_minutes,
+                ])?;
+            }
+        }
+        let deleted = tx.execute(
+            "DELETE FROM emotion_records WHERE datetime >= ?1 AND datetime < ?2",
+            [start, end],
+        )?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    pub fn get_rollups_by_date_range(&self, start_date: &str, end_date: &str) -> Result<Vec<EmotionRollup>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT datetime, timestamp, happy_count, calm_count, worried_count, tired_count,
+                    mean_confidence, focus_minutes
+             FROM emotion_rollups
+             WHERE datetime BETWEEN ?1 AND ?2
+             ORDER BY timestamp ASC"
+        )?;
+
+        let rollups = stmt.query_map([start_date, end_date], |row| {
+            Ok(EmotionRollup {
+                datetime: row.get(0)?,
+                timestamp: row.get(1)?,
+                happy_count: row.get(2)?,
+                calm_count: row.get(3)?,
+                worried_count: row.get(4)?,
+                tired_count: row.get(5)?,
+                mean_confidence: row.get(6)?,
+                focus_minutes: row.get(7)?,
+            })
+        })?;
+
+        rollups.collect()
+    }
+
+    /// Insert or replace the summary for `summary.date`
+    pub fn upsert_daily_summary(&self, summary: &DailySummary) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO daily_summaries (
+                date, emotion_index, stress_level,
+                total_records, valid_records,
+                focus_sessions, focus_minutes,
+                happy_count, calm_count, worried_count, tired_count,
+                checkin_count, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            rusqlite::params![
+                summary.date,
+                summary.emotion_index,
+                summary.stress_level,
+                summary.total_records,
+                summary.valid_records,
+                summary.focus_sessions,
+                summary.focus_minutes,
+                summary.happy_count,
+                summary.calm_count,
+                summary.worried_count,
+                summary.tired_count,
+                summary.checkin_count,
+                summary.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Summaries for `start_date..=end_date` ("YYYY-MM-DD"); days without data are absent
+    pub fn get_daily_summaries(&self, start_date: &str, end_date: &str) -> Result<Vec<DailySummary>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT date, emotion_index, stress_level, total_records, valid_records,
+                    focus_sessions, focus_minutes,
+                    happy_count, calm_count, worried_count, tired_count,
+                    checkin_count, updated_at
+             FROM daily_summaries
+             WHERE date BETWEEN ?1 AND ?2
+             ORDER BY date ASC"
+        )?;
+
+        let summaries = stmt.query_map([start_date, end_date], |row| {
+            Ok(DailySummary {
+                date: row.get(0)?,
+                emotion_index: row.get(1)?,
+                stress_level: row.get(2)?,
+                total_records: row.get(3)?,
+                valid_records: row.get(4)?,
+                focus_sessions: row.get(5)?,
+                focus_minutes: row.get(6)?,
+                happy_count: row.get(7)?,
+                calm_count: row.get(8)?,
+                worried_count: row.get(9)?,
+                tired_count: row.get(10)?,
+                checkin_count: row.get(11)?,
+                updated_at: row.get(12)?,
+            })
+        })?;
+
+        summaries.collect()
+    }
+
+    /// Days before `date` whose summary is missing, or counts fewer raw
+    /// records or check-ins than the day has now because some arrived late.
+    /// Days with rollups are left alone: retention has compacted them, so
+    /// their summary is the only full account of the day and recomputing it
+    /// from the raw rows left would lose data.
+    pub fn get_stale_summary_dates(&self, date: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "WITH days AS (
+                 SELECT date(datetime) AS day, COUNT(*) AS records, 0 AS checkins
+                 FROM emotion_records
+                 WHERE date(datetime) < date(?1)
+                 GROUP BY day
+                 UNION ALL
+                 SELECT date(datetime) AS day, 0, COUNT(*)
+                 FROM mood_checkins
+                 WHERE date(datetime) < date(?1)
+                 GROUP BY day
+             ), totals AS (
+                 SELECT day, SUM(records) AS records, SUM(checkins) AS checkins
+                 FROM days
+                 GROUP BY day
+             )
+             SELECT totals.day
+             FROM totals
+             LEFT JOIN daily_summaries AS summary ON summary.date = totals.day
+             WHERE ((summary.date IS NULL AND totals.records > 0)
+                    OR totals.records > summary.total_records
+                    OR totals.checkins > summary.checkin_count)
+               AND NOT EXISTS (
+                   SELECT 1 FROM emotion_rollups
+                   WHERE datetime BETWEEN totals.day || ' 00:00:00' AND totals.day || ' 23:59:59'
+               )
+             ORDER BY totals.day ASC"
+        )?;
+
+        let dates = stmt.query_map([date], |row| row.get(0))?;
+        dates.collect()
+    }
+
+    /// Insert or replace the mix called `mix.name`, layers included
+    pub fn save_sound_mix(&self, mix: &SoundMix) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO sound_mixes (name, master_volume, updated_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![mix.name, mix.master_volume, mix.updated_at],
+        )?;
+        tx.execute("DELETE FROM sound_mix_layers WHERE mix_name = ?1", [&mix.name])?;
+        for (position, layer) in mix.layers.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO sound_mix_layers (mix_name, position, sound_id, volume, muted)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![mix.name, position as i64, layer.sound_id, layer.volume, layer.muted],
+            )?;
+        }
+        tx.commit()
+    }
+
+    pub fn get_sound_mix(&self, name: &str) -> Result<Option<SoundMix>> {
+        Ok(self.query_sound_mixes("WHERE name = ?1", &[name])?.pop())
+    }
+
+    /// Every saved mix, by name
+    pub fn get_sound_mixes(&self) -> Result<Vec<SoundMix>> {
+        self.query_sound_mixes("", &[])
+    }
+
+    /// Returns whether a mix by that name existed
+    pub fn delete_sound_mix(&self, name: &str) -> Result<bool> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM sound_mix_layers WHERE mix_name = ?1", [name])?;
+        let deleted = tx.execute("DELETE FROM sound_mixes WHERE name = ?1", [name])?;
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+
+    fn query_sound_mixes(&self, filter: &str, params: &[&str]) -> Result<Vec<SoundMix>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT name, master_volume, updated_at FROM sound_mixes {} ORDER BY name ASC",
+            filter
+        ))?;
+        let mut layers = self.conn.prepare(
+            "SELECT sound_id, volume, muted FROM sound_mix_layers
+             WHERE mix_name = ?1
+             ORDER BY position ASC"
+        )?;
+
+        let mixes = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+            Ok(SoundMix {
+                name: row.get(0)?,
+                master_volume: row.get(1)?,
+                layers: Vec::new(),
+                updated_at: row.get(2)?,
+            })
+        })?;
+
+        mixes
+            .map(|mix| {
+                let mut mix = mix?;
+                mix.layers = layers
+                    .query_map([&mix.name], |row| {
+                        Ok(MixLayer {
+                            sound_id: row.get(0)?,
+                            volume: row.get(1)?,
+                            muted: row.get(2)?,
+                        })
+                    })?
+                    .collect::<Result<_>>()?;
+                Ok(mix)
+            })
+            .collect()
+    }
+
+    /// Insert frontend entries all-or-nothing; they must already be validated
+    pub fn insert_entries(&self, entries: &[EmotionEntry]) -> Result<Vec<i64>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut ids = Vec::with_capacity(entries.len());
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO emotion_entries (timestamp, datetime, emotion, confidence, source)
+                 VALUES (?1, ?2, ?3, ?4, ?5)"
+            )?;
+            for entry in entries {
+                stmt.execute(rusqlite::params![
+                    entry.timestamp,
+                    entry.datetime,
+                    entry.emotion,
+                    entry.confidence,
+                    entry.source,
+                ])?;
+                ids.push(tx.last_insert_rowid());
+            }
+        }
+        tx.commit()?;
+        Ok(ids)
+    }
+
+    pub fn get_entries_by_date_range(&self, start_date: &str, end_date: &str) -> Result<Vec<EmotionEntry>> {
+        self.query_emotions("emotion_entries", start_date, end_date)
+    }
+
+    /// Detected records and frontend entries together, as entries with source "detected"
+    pub fn get_all_emotions_by_date_range(&self, start_date: &str, end_date: &str) -> Result<Vec<EmotionEntry>> {
+        self.query_emotions("all_emotions", start_date, end_date)
+    }
+
+    fn query_emotions(&self, table: &str, start_date: &str, end_date: &str) -> Result<Vec<EmotionEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, timestamp, datetime, emotion, confidence, source
+             FROM {}
+             WHERE datetime BETWEEN ?1 AND ?2
+             ORDER BY timestamp ASC",
+            table
+        ))?;
+
+        let entries = stmt.query_map([start_date, end_date], |row| {
+            Ok(EmotionEntry {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                datetime: row.get(2)?,
+                emotion: row.get(3)?,
+                confidence: row.get(4)?,
+                source: row.get(5)?,
+            })
+        })?;
+
+        entries.collect()
+    }
+
+    pub fn insert_checkin(&self, checkin: &MoodCheckin) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO mood_checkins (timestamp, datetime, emotion, intensity, note)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                checkin.timestamp,
+                checkin.datetime,
+                checkin.emotion,
+                checkin.intensity,
+                checkin.note,
+            ],
+        )?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn get_checkins_by_date_range(&self, start_date: &str, end_date: &str) -> Result<Vec<MoodCheckin>> {
+        self.query_checkins("WHERE datetime BETWEEN ?1 AND ?2", &[start_date, end_date])
+    }
+
+    pub fn get_today_checkins(&self) -> Result<Vec<MoodCheckin>> {
+        self.query_checkins("WHERE date(datetime) = date('now', 'localtime')", &[])
+    }
+
+    fn query_checkins(&self, filter: &str, params: &[&str]) -> Result<Vec<MoodCheckin>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, timestamp, datetime, emotion, intensity, note
+             FROM mood_checkins
+             {}
+             ORDER BY timestamp ASC",
+            filter
+        ))?;
+
+        let checkins = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+            Ok(MoodCheckin {
                 id: Some(row.get(0)?),
                 timestamp: row.get(1)?,
                 datetime: row.get(2)?,
-                fer_emotion: row.get(3)?,
-                fer_confidence: row.get(4)?,
-                deepface_emotion: row.get(5)?,
-                deepface_confidence: row.get(6)?,
-                mapped_emotion: row.get(7)?,
-                work_minutes: row.get(8)?,
-                is_away: row.get::<_, i32>(9)? != 0,
-                has_face: row.get::<_, i32>(10)? != 0,
+                emotion: row.get(3)?,
+                intensity: row.get(4)?,
+                note: row.get(5)?,
             })
         })?;
 
-        records.collect()
+        checkins.collect()
     }
 }
@@ -3,9 +3,262 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::db::{Database, EmotionRecord};
+    use crate::db::{Database, EmotionEntry, EmotionRecord, MigrationError, MoodCheckin, SoundMix, SCHEMA_VERSION};
+    use crate::sounds::MixLayer;
     use std::path::PathBuf;
-    use chrono::Utc;
+    use chrono::{Local, TimeZone, Utc};
+    use rusqlite::Connection;
+
+    /// A database as shipped by every past schema version. Whenever a migration
+    /// is added, dump the previous version into fixtures/db and list it here.
+    const FIXTURES: &[(i64, &str)] = &[
+        (0, include_str!("../fixtures/db/v0.sql")),
+        (1, include_str!("../fixtures/db/v1.sql")),
+        (2, include_str!("../fixtures/db/v2.sql")),
+        (3, include_str!("../fixtures/db/v3.sql")),
+        (4, include_str!("../fixtures/db/v4.sql")),
+        (5, include_str!("../fixtures/db/v5.sql")),
+    ];
+
+    fn temp_db_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("moodpulse_{}.db", name));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    /// Tables with their columns, plus index names, for comparing schemas
+    fn schema_shape(path: &PathBuf) -> Vec<String> {
+        let conn = Connection::open(path).unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT type, name FROM sqlite_master
+             WHERE name NOT LIKE 'sqlite_%'
+             ORDER BY type, name"
+        ).unwrap();
+        let objects: Vec<(String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+
+        let mut shape = Vec::new();
+        for (kind, name) in objects {
+            shape.push(format!("{} {}", kind, name));
+            if kind == "table" {
+                let mut columns = conn.prepare(&format!("PRAGMA table_info({})", name)).unwrap();
+                let described = columns
+                    .query_map([], |row| {
+                        Ok(format!(
+                            "  {} {} notnull={} pk={}",
+                            row.get::<_, String>(1)?,
+                            row.get::<_, String>(2)?,
+                            row.get::<_, i64>(3)?,
+                            row.get::<_, i64>(5)?
+                        ))
+                    })
+                    .unwrap()
+                    .collect::<rusqlite::Result<Vec<_>>>()
+                    .unwrap();
+                shape.extend(described);
+            }
+        }
+        shape
+    }
+
+    #[test]
+    fn test_migrate_fixtures_from_every_version() {
+        let fresh_path = temp_db_path("migrate_fresh");
+        Database::new(fresh_path.clone()).unwrap().init().expect("Failed to create fresh database");
+        let expected = schema_shape(&fresh_path);
+
+        for (version, sql) in FIXTURES {
+            let path = temp_db_path(&format!("migrate_v{}", version));
+            {
+                let conn = Connection::open(&path).unwrap();
+                conn.execute_batch(sql).expect("Invalid fixture");
+                conn.pragma_update(None, "user_version", version).unwrap();
+            }
+
+            let db = Database::new(path.clone()).unwrap();
+            let before = db.get_recent_records(100).unwrap().len();
+            db.init().unwrap_or_else(|e| panic!("Migrating v{} failed: {}", version, e));
+
+            assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+            assert_eq!(db.get_recent_records(100).unwrap().len(), before, "v{} lost records", version);
+            drop(db);
+            assert_eq!(schema_shape(&path), expected, "v{} migrated to a different schema", version);
+
+            let _ = std::fs::remove_file(&path);
+        }
+        let _ = std::fs::remove_file(&fresh_path);
+    }
+
+    #[test]
+    fn test_init_is_idempotent() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.init().unwrap();
+        db.init().unwrap();
+        assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_newer_database_is_rejected() {
+        let path = temp_db_path("migrate_newer");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+        }
+
+        let db = Database::new(path.clone()).unwrap();
+        match db.init() {
+            Err(MigrationError::TooNew { found, supported }) => {
+                assert_eq!(found, SCHEMA_VERSION + 1);
+                assert_eq!(supported, SCHEMA_VERSION);
+            }
+            other => panic!("expected TooNew, got {:?}", other),
+        }
+        drop(db);
+        // Nothing was created in the newer file
+        assert!(schema_shape(&path).is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    fn entry(datetime: &str, emotion: &str) -> EmotionEntry {
+        EmotionEntry {
+            id: None,
+            timestamp: 0,
+            datetime: datetime.to_string(),
+            emotion: emotion.to_string(),
+            confidence: 1.0,
+            source: "manual".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_emotion_entry_validation() {
+        let mut valid = entry("2025-01-01T09:30:00", "calm");
+        valid.validate().expect("Valid entry rejected");
+        assert_eq!(valid.datetime, "2025-01-01 09:30:00");
+        assert!(valid.timestamp > 0);
+
+        let mut rfc3339 = entry("2025-01-01T09:30:00+00:00", "happy");
+        rfc3339.validate().expect("RFC 3339 entry rejected");
+
+        assert!(entry("2025-01-01 09:30:00", "ecstatic").validate().is_err());
+        assert!(entry("yesterday", "calm").validate().is_err());
+        let mut confident = entry("2025-01-01 09:30:00", "calm");
+        confident.confidence = 85.0;
+        assert!(confident.validate().is_err());
+        let mut unknown_source = entry("2025-01-01 09:30:00", "calm");
+        unknown_source.source = "camera".to_string();
+        assert!(unknown_source.validate().is_err());
+    }
+
+    #[test]
+    fn test_entries_query_alongside_records() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.init().unwrap();
+
+        let mut entries = vec![entry("2025-01-01 09:00:00", "happy"), entry("2025-01-01 11:00:00", "tired")];
+        for e in entries.iter_mut() {
+            e.validate().unwrap();
+        }
+        let ids = db.insert_entries(&entries).unwrap();
+        assert_eq!(ids.len(), 2);
+
+        let detected = EmotionRecord {
+            id: None,
+            timestamp: entries[0].timestamp + 3600,
+            datetime: "2025-01-01 10:00:00".to_string(),
+            fer_emotion: "worried".to_string(),
+            fer_confidence: 0.7,
+            deepface_emotion: None,
+            deepface_confidence: None,
+            mapped_emotion: "worried".to_string(),
+            work_minutes: 60.0,
+            is_away: false,
+            has_face: true,
+        };
+        db.insert_record(&detected).unwrap();
+
+        let start = "2025-01-01 00:00:00";
+        let end = "2025-01-01 23:59:59";
+        let saved = db.get_entries_by_date_range(start, end).unwrap();
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[0].id, Some(ids[0]));
+
+        let all = db.get_all_emotions_by_date_range(start, end).unwrap();
+        let emotions: Vec<(&str, &str)> = all.iter().map(|e| (e.emotion.as_str(), e.source.as_str())).collect();
+        assert_eq!(emotions, vec![("happy", "manual"), ("worried", "detected"), ("tired", "manual")]);
+    }
+
+    #[test]
+    fn test_insert_entries_is_all_or_nothing() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.init().unwrap();
+
+        let mut good = entry("2025-01-01 09:00:00", "happy");
+        good.validate().unwrap();
+        // Skipping validate lets the CHECK constraint reject the second row
+        let bad = entry("2025-01-01 10:00:00", "furious");
+        assert!(db.insert_entries(&[good, bad]).is_err());
+        assert!(db.get_entries_by_date_range("2025-01-01 00:00:00", "2025-01-01 23:59:59").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mood_checkins() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.init().unwrap();
+
+        let at = Local.with_ymd_and_hms(2025, 1, 1, 14, 0, 0).unwrap();
+        let checkin = MoodCheckin::new("tired".to_string(), 7, Some("  long meeting ".to_string()), at);
+        checkin.validate().unwrap();
+        let id = db.insert_checkin(&checkin).unwrap();
+
+        let blank = MoodCheckin::new("happy".to_string(), 3, Some("   ".to_string()), at);
+        assert_eq!(blank.note, None);
+        assert!(MoodCheckin::new("happy".to_string(), 0, None, at).validate().is_err());
+        assert!(MoodCheckin::new("happy".to_string(), 11, None, at).validate().is_err());
+        assert!(MoodCheckin::new("bored".to_string(), 5, None, at).validate().is_err());
+
+        let saved = db.get_checkins_by_date_range("2025-01-01 00:00:00", "2025-01-01 23:59:59").unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].id, Some(id));
+        assert_eq!(saved[0].note.as_deref(), Some("long meeting"));
+        assert_eq!(saved[0].intensity, 7);
+
+        db.insert_checkin(&MoodCheckin::new("calm".to_string(), 5, None, Local::now())).unwrap();
+        assert_eq!(db.get_today_checkins().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_sound_mixes() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.init().unwrap();
+
+        let at = Local.with_ymd_and_hms(2025, 1, 1, 14, 0, 0).unwrap();
+        let mut muted_cafe = MixLayer::new("cafe", 0.3);
+        muted_cafe.muted = true;
+        let mix = SoundMix::new("  focus ", 0.8, vec![MixLayer::new("rain", 0.6), muted_cafe], at);
+        mix.validate().unwrap();
+        assert!(SoundMix::new("   ", 0.8, vec![MixLayer::new("rain", 0.6)], at).validate().is_err());
+        assert!(SoundMix::new("empty", 0.8, Vec::new(), at).validate().is_err());
+
+        db.save_sound_mix(&mix).unwrap();
+        assert_eq!(db.get_sound_mix("focus").unwrap(), Some(mix.clone()));
+
+        // Saving under the same name replaces the layers rather than appending
+        let replaced = SoundMix::new("focus", 0.5, vec![MixLayer::new("fire", 1.0)], at);
+        db.save_sound_mix(&replaced).unwrap();
+        db.save_sound_mix(&SoundMix::new("evening", 0.4, vec![MixLayer::new("rain", 0.2)], at)).unwrap();
+        let names: Vec<String> = db.get_sound_mixes().unwrap().into_iter().map(|m| m.name).collect();
+        assert_eq!(names, ["evening", "focus"]);
+        assert_eq!(db.get_sound_mix("focus").unwrap().unwrap().layers, replaced.layers);
+
+        assert!(db.delete_sound_mix("focus").unwrap());
+        assert!(!db.delete_sound_mix("focus").unwrap());
+        assert_eq!(db.get_sound_mix("focus").unwrap(), None);
+    }
 
     #[test]
     fn test_database_operations() {
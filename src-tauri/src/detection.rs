@@ -0,0 +1,92 @@
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::db::{Database, EmotionRecord};
+
+/// Normalize a confidence to the 0-1 range (DeepFace reports percentages)
+fn normalize_confidence(conf: f64) -> f64 {
+    if conf > 1.0 { conf / 100.0 } else { conf }
+}
+
+/// One emotion estimate inside a detection response
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DetectedEmotion {
+    #[serde(default = "default_emotion")]
+    pub emotion: String,
+    #[serde(default)]
+    pub confidence: f64,
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+fn default_emotion() -> String {
+    "calm".to_string()
+}
+
+/// A detection response as produced by every EmotionBackend; this is also
+/// the payload of the `emotion-detected` event
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DetectionResult {
+    #[serde(default)]
+    pub has_face: bool,
+    #[serde(default)]
+    pub is_away: bool,
+    #[serde(default)]
+    pub work_minutes: f64,
+    #[serde(default)]
+    pub need_break_alert: bool,
+    #[serde(default)]
+    pub emotions: Vec<DetectedEmotion>,
+    /// When a replayed detection was originally recorded (unix ms); live
+    /// backends leave it unset
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub recorded_at_ms: Option<i64>,
+}
+
+impl DetectionResult {
+    pub fn parse(result: &str) -> Option<Self> {
+        serde_json::from_str(result).ok()
+    }
+
+    /// The recorded time for replayed detections, otherwise `now`
+    pub fn detected_at(&self, now: DateTime<Local>) -> DateTime<Local> {
+        self.recorded_at_ms
+            .and_then(|ms| Local.timestamp_millis_opt(ms).single())
+            .unwrap_or(now)
+    }
+}
+
+/// Build a database record from one detection response.
+/// Returns None when the JSON is malformed or no face emotion was detected.
+pub fn record_from_detection(result: &str, now: DateTime<Local>) -> Option<EmotionRecord> {
+    let data = DetectionResult::parse(result)?;
+    let first_emotion = data.emotions.first()?;
+    let second_emotion = data.emotions.get(1);
+
+    Some(EmotionRecord {
+        id: None,
+        timestamp: now.timestamp(),
+        datetime: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+        fer_emotion: first_emotion.emotion.clone(),
+        // Get confidence values and normalize to 0-1 range
+        fer_confidence: normalize_confidence(first_emotion.confidence),
+        deepface_emotion: second_emotion.map(|e| e.emotion.clone()),
+        deepface_confidence: second_emotion.map(|e| normalize_confidence(e.confidence)),
+        mapped_emotion: first_emotion.emotion.clone(),
+        work_minutes: data.work_minutes,
+        is_away: data.is_away,
+        has_face: data.has_face,
+    })
+}
+
+/// Persist a detection response, returning the new row id if one was written
+pub fn save_detection(db: &Database, result: &str, now: DateTime<Local>) -> rusqlite::Result<Option<i64>> {
+    match record_from_detection(result, now) {
+        Some(record) => db.insert_record(&record).map(Some),
+        None => Ok(None),
+    }
+}
@@ -1,9 +1,11 @@
-use crate::db::EmotionRecord;
+use crate::db::{DailySummary, EmotionRecord, EmotionRollup, MoodCheckin};
 use serde::{Deserialize, Serialize};
-use chrono::{NaiveDateTime, Timelike};
-use std::collections::HashMap;
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
+use std::collections::{HashMap, HashSet};
+use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct EmotionAnalysis {
     pub emotion_index: f64,      // 情绪指数 (1-10)
     pub stress_level: f64,        // 压力水平 (0-100)
@@ -11,15 +13,17 @@ pub struct EmotionAnalysis {
     pub valid_records: usize,     // 有效记录数
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct TimelinePoint {
-    pub time: String,           // 时间标签 "8:00"
+    pub time: String,           // 时间标签 "8:00"，跨天时 "2025-01-01 8:00"
     pub value: f64,             // 情绪值 (0-1)
     pub emoji: String,          // 表情符号
     pub emotion: String,        // 情绪类别
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct FocusAnalysis {
     pub total_focus_sessions: usize,  // 今日专注次数（超过30分钟）
     pub current_focus_duration: f64,  // 当前专注时长（分钟）
@@ -27,6 +31,88 @@ pub struct FocusAnalysis {
     pub total_focus_time: f64,        // 今日总专注时长（分钟）
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DayScore {
+    pub date: String,                 // 日期 "YYYY-MM-DD"
+    pub emotion_index: f64,           // 情绪指数 (1-10)
+    pub stress_level: f64,            // 压力水平 (0-100)
+    pub focus_minutes: f64,           // 专注时长（分钟）
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EmotionShare {
+    pub emotion: String,
+    #[ts(type = "number")]
+    pub count: i64,
+    pub share: f64,                   // 占比 (0-1)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PeriodChange {
+    pub emotion_index: f64,           // 平均情绪指数变化
+    pub stress_level: f64,            // 平均压力水平变化
+    pub focus_minutes: f64,           // 日均专注时长变化（分钟）
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WeekSummary {
+    pub start_date: String,
+    pub end_date: String,
+    pub average_emotion_index: Option<f64>,
+    pub average_stress_level: Option<f64>,
+    pub focus_minutes: f64,
+    pub change: Option<PeriodChange>, // 与前7天相比，任一方无数据时为空
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PeriodReport {
+    pub start_date: String,
+    pub end_date: String,
+    pub days: Vec<DayScore>,          // 有数据的每一天
+    pub best_day: Option<DayScore>,
+    pub worst_day: Option<DayScore>,
+    pub average_emotion_index: Option<f64>,
+    pub average_stress_level: Option<f64>,
+    pub emotion_distribution: Vec<EmotionShare>, // 按次数从多到少
+    pub dominant_emotion: Option<String>,
+    pub total_focus_minutes: f64,
+    #[ts(type = "number")]
+    pub focus_sessions: i64,          // 超过30分钟的专注次数
+    pub weeks: Vec<WeekSummary>,      // 从 start_date 起每7天一段
+    pub change: Option<PeriodChange>, // 与上一个等长周期相比
+}
+
+/// 默认自评权重，可用 MOODPULSE_CHECKIN_WEIGHT 或 set_checkin_weight 调整
+pub const DEFAULT_CHECKIN_WEIGHT: f64 = 0.3;
+
+/// 自评心情与检测记录的融合方式
+pub struct CheckinBlend<'a> {
+    pub checkins: &'a [MoodCheckin],
+    pub weight: f64,                  // 自评所占权重 (0-1)，0 表示只看检测记录
+}
+
+impl Default for CheckinBlend<'_> {
+    /// 不融合自评，只看检测记录
+    fn default() -> Self {
+        CheckinBlend { checkins: &[], weight: 0.0 }
+    }
+}
+
+impl<'a> CheckinBlend<'a> {
+    fn is_active(&self) -> bool {
+        self.weight > 0.0 && !self.checkins.is_empty()
+    }
+
+    fn weight(&self) -> f64 {
+        self.weight.clamp(0.0, 1.0)
+    }
+}
+
 /// 情绪类别到分数的映射
 fn emotion_to_score(emotion: &str) -> f64 {
     match emotion {
@@ -132,6 +218,59 @@ pub fn calculate_emotion_index(records: &[EmotionRecord]) -> f64 {
     emotion_index.max(1.0).min(10.0)
 }
 
+/// 自评心情的分数：强度决定偏离中性分的程度
+/// 例如 happy 强度10为10分，强度5为7.5分；worried 强度10为3分
+fn checkin_score(checkin: &MoodCheckin) -> f64 {
+    let neutral = emotion_to_score("neutral");
+    neutral + (emotion_to_score(&checkin.emotion) - neutral) * checkin.intensity as f64 / 10.0
+}
+
+/// 自评指数：与检测记录相同的指数衰减，越近的自评权重越高
+fn self_report_index(checkins: &[MoodCheckin]) -> Option<f64> {
+    if checkins.is_empty() {
+        return None;
+    }
+
+    let n = checkins.len();
+    let mut total_weighted_score = 0.0;
+    let mut total_weight = 0.0;
+    for (i, checkin) in checkins.iter().enumerate() {
+        let time_weight = (-0.1 * (n - i - 1) as f64).exp();
+        total_weighted_score += checkin_score(checkin) * time_weight;
+        total_weight += time_weight;
+    }
+    Some(total_weighted_score / total_weight)
+}
+
+/// 按权重融合检测分数与自评分数；只有一方有数据时直接使用该方
+fn blend_scores(detected: Option<f64>, reported: Option<f64>, weight: f64) -> Option<f64> {
+    match (detected, reported) {
+        (Some(d), Some(r)) => Some(d * (1.0 - weight) + r * weight),
+        (Some(d), None) => Some(d),
+        (None, Some(r)) => Some(r),
+        (None, None) => None,
+    }
+}
+
+/// 计算融合自评后的情绪指数（10分制）
+pub fn calculate_emotion_index_blended(records: &[EmotionRecord], blend: &CheckinBlend) -> f64 {
+    let has_valid_records = records.iter().any(|r| r.has_face && !r.is_away);
+    let detected = if has_valid_records {
+        Some(calculate_emotion_index(records))
+    } else {
+        None
+    };
+    let reported = if blend.is_active() {
+        self_report_index(blend.checkins)
+    } else {
+        None
+    };
+
+    blend_scores(detected, reported, blend.weight())
+        .unwrap_or(5.0)
+        .clamp(1.0, 10.0)
+}
+
 /// 计算压力水平（百分制，0-100）
 /// 基于情绪波动惩罚模型
 pub fn calculate_stress_level(records: &[EmotionRecord]) -> f64 {
@@ -193,16 +332,16 @@ pub fn calculate_stress_level(records: &[EmotionRecord]) -> f64 {
     total_stress.max(0.0).min(100.0)
 }
 
-/// 分析今日情绪数据
-pub fn analyze_today_emotions(records: Vec<EmotionRecord>) -> EmotionAnalysis {
+/// 分析今日情绪数据，情绪指数按 blend 融合自评心情
+pub fn analyze_today_emotions(records: &[EmotionRecord], blend: &CheckinBlend) -> EmotionAnalysis {
     let total_records = records.len();
     let valid_records = records
         .iter()
         .filter(|r| r.has_face && !r.is_away)
         .count();
 
-    let emotion_index = calculate_emotion_index(&records);
-    let stress_level = calculate_stress_level(&records);
+    let emotion_index = calculate_emotion_index_blended(records, blend);
+    let stress_level = calculate_stress_level(records);
 
     EmotionAnalysis {
         emotion_index: (emotion_index * 100.0).round() / 100.0, // 保留2位小数
@@ -212,36 +351,98 @@ pub fn analyze_today_emotions(records: Vec<EmotionRecord>) -> EmotionAnalysis {
     }
 }
 
-/// 获取情绪时间线（30分钟间隔）
-pub fn get_emotion_timeline(records: Vec<EmotionRecord>) -> Vec<TimelinePoint> {
+/// 30分钟间隔：(日期，只在跨天时有), 小时, 分钟(0或30)
+type IntervalKey = (Option<NaiveDate>, u32, u32);
+
+fn interval_key(datetime: &str, with_date: bool) -> Option<IntervalKey> {
+    let dt = NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d %H:%M:%S").ok()?;
+    let minute = if dt.minute() < 30 { 0 } else { 30 };
+    Some((with_date.then_some(dt.date()), dt.hour(), minute))
+}
+
+/// 时间标签，如 "8:00"、"8:30"，跨天时为 "2025-01-01 8:30"
+fn interval_label((date, hour, minute): IntervalKey) -> String {
+    match date {
+        Some(date) => format!("{} {}:{:02}", date.format("%Y-%m-%d"), hour, minute),
+        None => format!("{}:{:02}", hour, minute),
+    }
+}
+
+fn average(scores: Option<&Vec<f64>>) -> Option<f64> {
+    scores
+        .filter(|s| !s.is_empty())
+        .map(|s| s.iter().sum::<f64>() / s.len() as f64)
+}
+
+/// 获取情绪时间线（30分钟间隔），按 blend 融合自评心情
+pub fn get_emotion_timeline(records: &[EmotionRecord], blend: &CheckinBlend) -> Vec<TimelinePoint> {
+    get_emotion_timeline_in_range(records, &[], blend, false)
+}
+
+/// 日期范围内的情绪时间线：已压缩的日子用半小时汇总 rollups 代替原始记录；
+/// 跨天时每个点带上日期，各天分开而不是平均成一天
+pub fn get_emotion_timeline_in_range(
+    records: &[EmotionRecord],
+    rollups: &[EmotionRollup],
+    blend: &CheckinBlend,
+    multi_day: bool,
+) -> Vec<TimelinePoint> {
     let valid_records: Vec<&EmotionRecord> = records
         .iter()
         .filter(|r| r.has_face && !r.is_away)
         .collect();
+    let checkins: &[MoodCheckin] = if blend.is_active() { blend.checkins } else { &[] };
 
-    if valid_records.is_empty() {
+    if valid_records.is_empty() && rollups.is_empty() && checkins.is_empty() {
         return Vec::new();
     }
 
-    // 按30分钟间隔分组
-    let mut interval_map: HashMap<String, Vec<f64>> = HashMap::new();
+    // 按30分钟间隔分组：检测分数累计 (总分, 次数)
+    let mut interval_map: HashMap<IntervalKey, (f64, f64)> = HashMap::new();
+    let mut checkin_map: HashMap<IntervalKey, Vec<f64>> = HashMap::new();
 
     for record in valid_records {
-        if let Ok(dt) = NaiveDateTime::parse_from_str(&record.datetime, "%Y-%m-%d %H:%M:%S") {
-            let hour = dt.hour();
-            let minute = if dt.minute() < 30 { 0 } else { 30 };
-            let time_key = format!("{}:{:02}", hour, minute);
+        if let Some(key) = interval_key(&record.datetime, multi_day) {
+            let entry = interval_map.entry(key).or_default();
+            entry.0 += emotion_to_score(&record.mapped_emotion);
+            entry.1 += 1.0;
+        }
+    }
 
-            let score = emotion_to_score(&record.mapped_emotion);
-            interval_map.entry(time_key).or_insert_with(Vec::new).push(score);
+    // 一条 rollup 相当于该半小时内各情绪的若干条记录
+    for rollup in rollups {
+        if let Some(key) = interval_key(&rollup.datetime, multi_day) {
+            let entry = interval_map.entry(key).or_default();
+            for (emotion, count) in [
+                ("happy", rollup.happy_count),
+                ("calm", rollup.calm_count),
+                ("worried", rollup.worried_count),
+                ("tired", rollup.tired_count),
+            ] {
+                entry.0 += emotion_to_score(emotion) * count as f64;
+                entry.1 += count as f64;
+            }
         }
     }
 
-    // 计算每个间隔的平均值并转换为TimelinePoint
-    let mut timeline: Vec<TimelinePoint> = interval_map
-        .iter()
-        .map(|(time, scores)| {
-            let avg_score = scores.iter().sum::<f64>() / scores.len() as f64;
+    for checkin in checkins {
+        if let Some(key) = interval_key(&checkin.datetime, multi_day) {
+            checkin_map.entry(key).or_default().push(checkin_score(checkin));
+        }
+    }
+
+    // 按时间排序
+    let mut keys: Vec<IntervalKey> = interval_map.keys().chain(checkin_map.keys()).copied().collect();
+    keys.sort();
+    keys.dedup();
+
+    // 计算每个间隔的平均值（有自评时按权重融合）并转换为TimelinePoint
+    keys.into_iter()
+        .filter_map(|key| {
+            let detected = interval_map.get(&key)
+                .filter(|(_, count)| *count > 0.0)
+                .map(|(total, count)| total / count);
+            let avg_score = blend_scores(detected, average(checkin_map.get(&key)), blend.weight())?;
             let value = (avg_score / 10.0 * 100.0).round() / 100.0; // 转换为0-1范围
 
             let (emoji, emotion) = if avg_score >= 8.0 {
@@ -254,27 +455,14 @@ pub fn get_emotion_timeline(records: Vec<EmotionRecord>) -> Vec<TimelinePoint> {
                 ("😟".to_string(), "worried".to_string())
             };
 
-            TimelinePoint {
-                time: time.clone(),
+            Some(TimelinePoint {
+                time: interval_label(key),
                 value,
                 emoji,
                 emotion,
-            }
+            })
         })
-        .collect();
-
-    // 按时间排序
-    timeline.sort_by(|a, b| {
-        let parse_time = |s: &str| -> (u32, u32) {
-            let parts: Vec<&str> = s.split(':').collect();
-            (parts[0].parse().unwrap_or(0), parts[1].parse().unwrap_or(0))
-        };
-        let (ah, am) = parse_time(&a.time);
-        let (bh, bm) = parse_time(&b.time);
-        (ah, am).cmp(&(bh, bm))
-    });
-
-    timeline
+        .collect()
 }
 
 /// 分析专注时长
@@ -373,6 +561,259 @@ pub fn analyze_focus_time(records: Vec<EmotionRecord>) -> FocusAnalysis {
     }
 }
 
+/// 汇总一天的情绪指数、压力、专注和各情绪次数，存入 daily_summaries
+pub fn summarize_day(date: &str, records: Vec<EmotionRecord>, blend: &CheckinBlend, updated_at: i64) -> DailySummary {
+    summarize(date, records, blend, updated_at).2
+}
+
+/// 当天推送给前端的分析、时间线、专注，和要存的汇总行，共用一次计算
+pub struct DayAnalyses {
+    pub analysis: EmotionAnalysis,
+    pub timeline: Vec<TimelinePoint>,
+    pub focus: FocusAnalysis,
+    pub summary: DailySummary,
+}
+
+pub fn analyze_day(date: &str, records: Vec<EmotionRecord>, blend: &CheckinBlend, updated_at: i64) -> DayAnalyses {
+    let timeline = get_emotion_timeline(&records, blend);
+    let (analysis, focus, summary) = summarize(date, records, blend, updated_at);
+    DayAnalyses { analysis, timeline, focus, summary }
+}
+
+fn summarize(date: &str, records: Vec<EmotionRecord>, blend: &CheckinBlend, updated_at: i64) -> (EmotionAnalysis, FocusAnalysis, DailySummary) {
+    let mut counts: HashMap<String, i64> = HashMap::new();
+    for record in &records {
+        *counts.entry(record.mapped_emotion.clone()).or_default() += 1;
+    }
+    let count = |emotion: &str| counts.get(emotion).copied().unwrap_or(0);
+
+    let analysis = analyze_today_emotions(&records, blend);
+    let focus = analyze_focus_time(records);
+
+    let summary = DailySummary {
+        date: date.to_string(),
+        emotion_index: analysis.emotion_index,
+        stress_level: analysis.stress_level,
+        total_records: analysis.total_records as i64,
+        valid_records: analysis.valid_records as i64,
+        focus_sessions: focus.total_focus_sessions as i64,
+        focus_minutes: focus.total_focus_time,
+        happy_count: count("happy"),
+        calm_count: count("calm"),
+        worried_count: count("worried"),
+        tired_count: count("tired"),
+        checkin_count: blend.checkins.len() as i64,
+        updated_at,
+    };
+    (analysis, focus, summary)
+}
+
+/// 已被保留策略压缩的日子：有半小时汇总，或原始记录已全部删除。
+/// 压缩后补进来的迟到记录不会让这一天退回到只用原始记录
+fn compacted_days<'a>(records: &[EmotionRecord], summaries: &'a [DailySummary], rollups: &[EmotionRollup]) -> Vec<&'a DailySummary> {
+    let raw_days: HashSet<&str> = records.iter().filter_map(|r| r.datetime.get(..10)).collect();
+    let rollup_days: HashSet<&str> = rollups.iter().filter_map(|r| r.datetime.get(..10)).collect();
+    summaries
+        .iter()
+        .filter(|s| has_data(s))
+        .filter(|s| rollup_days.contains(s.date.as_str()) || !raw_days.contains(s.date.as_str()))
+        .collect()
+}
+
+/// 日期范围内的情绪分析：未压缩的日子用原始记录现算，已压缩的日子用每日汇总
+/// 加上压缩后迟到的原始记录，两部分按有效记录数加权合并
+pub fn analyze_emotions_in_range(
+    records: &[EmotionRecord],
+    summaries: &[DailySummary],
+    rollups: &[EmotionRollup],
+    blend: &CheckinBlend,
+) -> EmotionAnalysis {
+    let compacted = compacted_days(records, summaries, rollups);
+    if compacted.is_empty() {
+        return analyze_today_emotions(records, blend);
+    }
+
+    // 已压缩日子的自评已经融合进它们的汇总
+    let checkins: Vec<MoodCheckin> = blend.checkins
+        .iter()
+        .filter(|c| !compacted.iter().any(|s| c.datetime.starts_with(&s.date)))
+        .cloned()
+        .collect();
+    let raw_blend = CheckinBlend { checkins: &checkins, weight: blend.weight };
+    let raw = analyze_today_emotions(records, &raw_blend);
+
+    // (情绪指数, 压力, 权重)，只有自评的部分按1条记录计
+    let mut parts: Vec<(f64, f64, f64)> = compacted
+        .iter()
+        .map(|s| (s.emotion_index, s.stress_level, s.valid_records.max(1) as f64))
+        .collect();
+    if raw.valid_records > 0 || raw_blend.is_active() {
+        parts.push((raw.emotion_index, raw.stress_level, raw.valid_records.max(1) as f64));
+    }
+    let total_weight: f64 = parts.iter().map(|p| p.2).sum();
+    let emotion_index = parts.iter().map(|p| p.0 * p.2).sum::<f64>() / total_weight;
+    let stress_level = parts.iter().map(|p| p.1 * p.2).sum::<f64>() / total_weight;
+
+    EmotionAnalysis {
+        emotion_index: round_to(emotion_index, 2),
+        stress_level: round_to(stress_level, 2),
+        total_records: raw.total_records + compacted.iter().map(|s| s.total_records as usize).sum::<usize>(),
+        valid_records: raw.valid_records + compacted.iter().map(|s| s.valid_records as usize).sum::<usize>(),
+    }
+}
+
+/// 日期范围内的专注分析：已压缩的日子计入其汇总的专注次数和时长
+pub fn analyze_focus_time_in_range(records: Vec<EmotionRecord>, summaries: &[DailySummary], rollups: &[EmotionRollup]) -> FocusAnalysis {
+    let compacted = compacted_days(&records, summaries, rollups);
+    let sessions: i64 = compacted.iter().map(|s| s.focus_sessions).sum();
+    let minutes: f64 = compacted.iter().map(|s| s.focus_minutes).sum();
+
+    let mut focus = analyze_focus_time(records);
+    focus.total_focus_sessions += sessions as usize;
+    focus.total_focus_time = round_to(focus.total_focus_time + minutes, 1);
+    focus
+}
+
+fn round_to(value: f64, places: i32) -> f64 {
+    let factor = 10f64.powi(places);
+    (value * factor).round() / factor
+}
+
+/// 有检测记录或自评的日子才计入报告
+fn has_data(summary: &DailySummary) -> bool {
+    summary.total_records > 0 || summary.checkin_count > 0
+}
+
+fn days_between(summaries: &[DailySummary], start: NaiveDate, end: NaiveDate) -> Vec<&DailySummary> {
+    let (start, end) = (start.format("%Y-%m-%d").to_string(), end.format("%Y-%m-%d").to_string());
+    summaries
+        .iter()
+        .filter(|s| has_data(s) && s.date >= start && s.date <= end)
+        .collect()
+}
+
+/// (平均情绪指数, 平均压力, 日均专注分钟)，无数据时为 None
+fn period_averages(days: &[&DailySummary]) -> Option<(f64, f64, f64)> {
+    if days.is_empty() {
+        return None;
+    }
+    let n = days.len() as f64;
+    Some((
+        days.iter().map(|d| d.emotion_index).sum::<f64>() / n,
+        days.iter().map(|d| d.stress_level).sum::<f64>() / n,
+        days.iter().map(|d| d.focus_minutes).sum::<f64>() / n,
+    ))
+}
+
+fn period_change(current: &[&DailySummary], previous: &[&DailySummary]) -> Option<PeriodChange> {
+    let (index, stress, focus) = period_averages(current)?;
+    let (prev_index, prev_stress, prev_focus) = period_averages(previous)?;
+    Some(PeriodChange {
+        emotion_index: round_to(index - prev_index, 2),
+        stress_level: round_to(stress - prev_stress, 2),
+        focus_minutes: round_to(focus - prev_focus, 1),
+    })
+}
+
+fn day_score(summary: &DailySummary) -> DayScore {
+    DayScore {
+        date: summary.date.clone(),
+        emotion_index: summary.emotion_index,
+        stress_level: summary.stress_level,
+        focus_minutes: summary.focus_minutes,
+    }
+}
+
+/// 报告需要的最早日期：上一个等长周期，且至少往前7天以便计算首周环比
+pub fn comparison_start(start: NaiveDate, end: NaiveDate) -> NaiveDate {
+    let length = (end - start).num_days() + 1;
+    start - Duration::days(length.max(7))
+}
+
+/// 生成周报/月报。summaries 应覆盖 comparison_start(start, end) 到 end，
+/// 范围外的日子只用于环比
+pub fn build_period_report(start: NaiveDate, end: NaiveDate, summaries: &[DailySummary]) -> PeriodReport {
+    let current = days_between(summaries, start, end);
+    let length = (end - start).num_days() + 1;
+    let previous = days_between(
+        summaries,
+        start - Duration::days(length),
+        start - Duration::days(1),
+    );
+
+    let days: Vec<DayScore> = current.iter().map(|d| day_score(d)).collect();
+
+    // 最好/最差的一天，并列时取较早的一天
+    let mut best_day: Option<&DayScore> = None;
+    let mut worst_day: Option<&DayScore> = None;
+    for day in &days {
+        match best_day {
+            Some(best) if day.emotion_index <= best.emotion_index => {}
+            _ => best_day = Some(day),
+        }
+        match worst_day {
+            Some(worst) if day.emotion_index >= worst.emotion_index => {}
+            _ => worst_day = Some(day),
+        }
+    }
+
+    // 情绪分布
+    let counts = [
+        ("happy", current.iter().map(|d| d.happy_count).sum::<i64>()),
+        ("calm", current.iter().map(|d| d.calm_count).sum::<i64>()),
+        ("worried", current.iter().map(|d| d.worried_count).sum::<i64>()),
+        ("tired", current.iter().map(|d| d.tired_count).sum::<i64>()),
+    ];
+    let total: i64 = counts.iter().map(|(_, count)| count).sum();
+    let mut emotion_distribution: Vec<EmotionShare> = counts
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(emotion, count)| EmotionShare {
+            emotion: emotion.to_string(),
+            count: *count,
+            share: round_to(*count as f64 / total as f64, 4),
+        })
+        .collect();
+    // 稳定排序，次数相同时保持 happy/calm/worried/tired 的顺序
+    emotion_distribution.sort_by_key(|share| std::cmp::Reverse(share.count));
+
+    // 每7天一段，与前7天比较
+    let mut weeks = Vec::new();
+    let mut week_start = start;
+    while week_start <= end {
+        let week_end = (week_start + Duration::days(6)).min(end);
+        let week = days_between(summaries, week_start, week_end);
+        let before = days_between(summaries, week_start - Duration::days(7), week_start - Duration::days(1));
+        let averages = period_averages(&week);
+        weeks.push(WeekSummary {
+            start_date: week_start.format("%Y-%m-%d").to_string(),
+            end_date: week_end.format("%Y-%m-%d").to_string(),
+            average_emotion_index: averages.map(|(index, _, _)| round_to(index, 2)),
+            average_stress_level: averages.map(|(_, stress, _)| round_to(stress, 2)),
+            focus_minutes: round_to(week.iter().map(|d| d.focus_minutes).sum(), 1),
+            change: period_change(&week, &before),
+        });
+        week_start += Duration::days(7);
+    }
+
+    let averages = period_averages(&current);
+    PeriodReport {
+        start_date: start.format("%Y-%m-%d").to_string(),
+        end_date: end.format("%Y-%m-%d").to_string(),
+        best_day: best_day.cloned(),
+        worst_day: worst_day.cloned(),
+        average_emotion_index: averages.map(|(index, _, _)| round_to(index, 2)),
+        average_stress_level: averages.map(|(_, stress, _)| round_to(stress, 2)),
+        dominant_emotion: emotion_distribution.first().map(|share| share.emotion.clone()),
+        emotion_distribution,
+        total_focus_minutes: round_to(current.iter().map(|d| d.focus_minutes).sum(), 1),
+        focus_sessions: current.iter().map(|d| d.focus_sessions).sum(),
+        weeks,
+        change: period_change(&current, &previous),
+        days,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -397,4 +838,223 @@ mod tests {
         let stress = calculate_stress_level(&records);
         assert_eq!(stress, 50.0);
     }
+
+    fn record(datetime: &str, emotion: &str) -> EmotionRecord {
+        EmotionRecord {
+            id: None,
+            timestamp: 0,
+            datetime: datetime.to_string(),
+            fer_emotion: emotion.to_string(),
+            fer_confidence: 0.8,
+            deepface_emotion: None,
+            deepface_confidence: None,
+            mapped_emotion: emotion.to_string(),
+            work_minutes: 0.0,
+            is_away: false,
+            has_face: true,
+        }
+    }
+
+    fn checkin(datetime: &str, emotion: &str, intensity: u8) -> MoodCheckin {
+        MoodCheckin {
+            id: None,
+            timestamp: 0,
+            datetime: datetime.to_string(),
+            emotion: emotion.to_string(),
+            intensity,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_checkin_score_scales_with_intensity() {
+        assert_eq!(checkin_score(&checkin("2025-01-01 09:00:00", "happy", 10)), 10.0);
+        assert_eq!(checkin_score(&checkin("2025-01-01 09:00:00", "happy", 5)), 7.5);
+        assert_eq!(checkin_score(&checkin("2025-01-01 09:00:00", "worried", 10)), 3.0);
+    }
+
+    #[test]
+    fn test_emotion_index_blend_weight() {
+        let records = vec![record("2025-01-01 09:00:00", "worried"); 3];
+        let checkins = vec![checkin("2025-01-01 09:10:00", "happy", 10)];
+        let detected = calculate_emotion_index(&records);
+
+        // No weight, or no check-ins, leaves the detection index alone
+        assert_eq!(calculate_emotion_index_blended(&records, &CheckinBlend::default()), detected);
+        let ignored = CheckinBlend { checkins: &checkins, weight: 0.0 };
+        assert_eq!(calculate_emotion_index_blended(&records, &ignored), detected);
+
+        let half = CheckinBlend { checkins: &checkins, weight: 0.5 };
+        let blended = calculate_emotion_index_blended(&records, &half);
+        assert!((blended - (detected + 10.0) / 2.0).abs() < 1e-9);
+
+        // Self-reports alone still produce an index
+        assert_eq!(calculate_emotion_index_blended(&[], &half), 10.0);
+    }
+
+    #[test]
+    fn test_timeline_blends_checkins_per_interval() {
+        let records = vec![record("2025-01-01 09:05:00", "worried"), record("2025-01-01 10:05:00", "calm")];
+        let checkins = vec![checkin("2025-01-01 09:20:00", "happy", 10), checkin("2025-01-01 11:40:00", "calm", 10)];
+        let blend = CheckinBlend { checkins: &checkins, weight: 0.5 };
+
+        let timeline = get_emotion_timeline(&records, &blend);
+        let times: Vec<&str> = timeline.iter().map(|p| p.time.as_str()).collect();
+        assert_eq!(times, vec!["9:00", "10:00", "11:30"]);
+        // (3 + 10) / 2 = 6.5
+        assert_eq!(timeline[0].value, 0.65);
+        assert_eq!(timeline[1].value, 0.7);
+        assert_eq!(timeline[2].value, 0.7);
+
+        let detected_only = get_emotion_timeline(&records, &CheckinBlend::default());
+        assert_eq!(detected_only.len(), 2);
+        assert_eq!(detected_only[0].value, 0.3);
+    }
+
+    fn summary(date: &str, emotion_index: f64, happy: i64, worried: i64, focus_minutes: f64) -> DailySummary {
+        DailySummary {
+            date: date.to_string(),
+            emotion_index,
+            stress_level: 100.0 - emotion_index * 10.0,
+            total_records: happy + worried,
+            valid_records: happy + worried,
+            focus_sessions: 1,
+            focus_minutes,
+            happy_count: happy,
+            calm_count: 0,
+            worried_count: worried,
+            tired_count: 0,
+            checkin_count: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_timeline_keeps_days_apart_across_a_range() {
+        let records = vec![record("2025-01-01 09:05:00", "worried"), record("2025-01-02 09:10:00", "happy")];
+
+        let timeline = get_emotion_timeline_in_range(&records, &[], &CheckinBlend::default(), true);
+        let points: Vec<(&str, f64)> = timeline.iter().map(|p| (p.time.as_str(), p.value)).collect();
+        assert_eq!(points, vec![("2025-01-01 9:00", 0.3), ("2025-01-02 9:00", 1.0)]);
+
+        // 单日仍是 "H:MM"
+        let one_day = get_emotion_timeline_in_range(&records[..1], &[], &CheckinBlend::default(), false);
+        assert_eq!(one_day[0].time, "9:00");
+    }
+
+    fn rollup(datetime: &str, happy: i64, worried: i64) -> EmotionRollup {
+        EmotionRollup {
+            timestamp: 0,
+            datetime: datetime.to_string(),
+            happy_count: happy,
+            calm_count: 0,
+            worried_count: worried,
+            tired_count: 0,
+            mean_confidence: 0.8,
+            focus_minutes: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_compacted_days_fall_back_to_summaries_and_rollups() {
+        // 1日已被压缩，只剩汇总；2日还有原始记录，它的汇总不应重复计入
+        let summaries = vec![summary("2025-01-01", 8.0, 3, 1, 45.0), summary("2025-01-02", 2.0, 0, 9, 0.0)];
+        let records = vec![record("2025-01-02 10:05:00", "calm"); 3];
+        let blend = CheckinBlend::default();
+
+        let raw = analyze_today_emotions(&records, &blend);
+        let analysis = analyze_emotions_in_range(&records, &summaries, &[], &blend);
+        assert_eq!((analysis.total_records, analysis.valid_records), (7, 7));
+        assert_eq!(analysis.emotion_index, round_to((8.0 * 4.0 + raw.emotion_index * 3.0) / 7.0, 2));
+
+        let compacted_only = analyze_emotions_in_range(&[], &summaries[..1], &[], &blend);
+        assert_eq!((compacted_only.emotion_index, compacted_only.stress_level), (8.0, 20.0));
+        assert_eq!(compacted_only.total_records, 4);
+
+        let focus = analyze_focus_time_in_range(records.clone(), &summaries, &[]);
+        assert_eq!((focus.total_focus_sessions, focus.total_focus_time), (1, 45.0));
+
+        // (2×10 + 2×3) / 4 = 6.5
+        let rollups = vec![rollup("2025-01-01 09:00:00", 2, 2)];
+        let timeline = get_emotion_timeline_in_range(&records, &rollups, &blend, true);
+        let points: Vec<(&str, f64)> = timeline.iter().map(|p| (p.time.as_str(), p.value)).collect();
+        assert_eq!(points, vec![("2025-01-01 9:00", 0.65), ("2025-01-02 10:00", 0.7)]);
+    }
+
+    #[test]
+    fn test_late_record_on_compacted_day_keeps_its_summary() {
+        // 1日压缩后又补进一条原始记录：汇总照用，迟到的记录另外计入
+        let summaries = vec![summary("2025-01-01", 8.0, 3, 1, 45.0)];
+        let rollups = vec![rollup("2025-01-01 09:00:00", 3, 1)];
+        let records = vec![record("2025-01-01 22:00:00", "worried")];
+        let blend = CheckinBlend::default();
+
+        let analysis = analyze_emotions_in_range(&records, &summaries, &rollups, &blend);
+        assert_eq!((analysis.total_records, analysis.valid_records), (5, 5));
+        assert_eq!(analysis.emotion_index, round_to((8.0 * 4.0 + 3.0) / 5.0, 2));
+
+        let focus = analyze_focus_time_in_range(records, &summaries, &rollups);
+        assert_eq!((focus.total_focus_sessions, focus.total_focus_time), (1, 45.0));
+    }
+
+    #[test]
+    fn test_period_report_days_and_distribution() {
+        let summaries = vec![
+            summary("2025-01-06", 6.0, 3, 1, 60.0),
+            summary("2025-01-07", 8.0, 5, 0, 90.0),
+            // 无记录的日子不计入
+            summary("2025-01-08", 5.0, 0, 0, 0.0),
+            summary("2025-01-09", 4.0, 1, 6, 30.0),
+            // 范围外
+            summary("2025-01-13", 9.0, 9, 0, 120.0),
+        ];
+
+        let report = build_period_report(date("2025-01-06"), date("2025-01-12"), &summaries);
+        assert_eq!(report.days.len(), 3);
+        assert_eq!(report.best_day.as_ref().unwrap().date, "2025-01-07");
+        assert_eq!(report.worst_day.as_ref().unwrap().date, "2025-01-09");
+        assert_eq!(report.average_emotion_index, Some(6.0));
+        assert_eq!(report.total_focus_minutes, 180.0);
+        assert_eq!(report.focus_sessions, 3);
+
+        assert_eq!(report.dominant_emotion.as_deref(), Some("happy"));
+        assert_eq!(report.emotion_distribution.len(), 2);
+        assert_eq!(report.emotion_distribution[0].count, 9);
+        assert!((report.emotion_distribution[1].share - 7.0 / 16.0).abs() < 1e-4);
+
+        // 没有上一周期的数据
+        assert_eq!(report.change, None);
+        assert_eq!(report.weeks.len(), 1);
+    }
+
+    #[test]
+    fn test_period_report_week_over_week() {
+        let summaries = vec![
+            summary("2024-12-30", 5.0, 1, 1, 40.0),
+            summary("2025-01-06", 7.0, 2, 0, 60.0),
+            summary("2025-01-13", 6.0, 1, 1, 30.0),
+        ];
+
+        let start = date("2025-01-06");
+        let end = date("2025-01-19");
+        assert_eq!(comparison_start(start, end), date("2024-12-23"));
+
+        let report = build_period_report(start, end, &summaries);
+        assert_eq!(report.weeks.len(), 2);
+        assert_eq!(report.weeks[0].end_date, "2025-01-12");
+        assert_eq!(
+            report.weeks[0].change,
+            Some(PeriodChange { emotion_index: 2.0, stress_level: -20.0, focus_minutes: 20.0 })
+        );
+        assert_eq!(
+            report.weeks[1].change,
+            Some(PeriodChange { emotion_index: -1.0, stress_level: 10.0, focus_minutes: -30.0 })
+        );
+        // 上一个14天只有 12-30 一天
+        assert_eq!(report.change.unwrap().emotion_index, 1.5);
+    }
 }
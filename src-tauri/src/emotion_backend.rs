@@ -0,0 +1,134 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Duration;
+use ts_rs::TS;
+
+use crate::emotion_service::{DetectError, EmotionService, ServiceStatus, StartError};
+use crate::service_log::LogLine;
+
+/// A source of emotion detections. `detect` returns the same JSON document
+/// the Python service produces (`has_face`, `is_away`, `work_minutes`,
+/// `need_break_alert`, `emotions`), so everything downstream of
+/// `detect_emotion` works unchanged whichever backend is configured.
+pub trait EmotionBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn start(&self) -> Result<(), StartError>;
+
+    /// Run one detection; `None` uses the backend's default deadline
+    fn detect(&self, timeout: Option<Duration>) -> Result<String, DetectError>;
+
+    fn stop(&self) -> Result<(), String>;
+
+    fn health(&self) -> BackendHealth;
+
+    /// Recent diagnostic output, oldest first
+    fn logs(&self, _limit: usize) -> Vec<LogLine> {
+        Vec::new()
+    }
+
+    /// Record raw responses to a replayable JSONL file; `None` stops
+    fn set_recording(&self, _path: Option<PathBuf>) -> Result<(), String> {
+        Err(format!("The {} backend does not support recording", self.name()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BackendHealth {
+    pub backend: String,
+    #[serde(flatten)]
+    pub status: ServiceStatus,
+}
+
+/// Which backend to run, chosen with `MOODPULSE_EMOTION_BACKEND`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendKind {
+    Python,
+    /// Scripted detections, see mock_backend.rs
+    Mock,
+    /// A recorded session file, see replay_backend.rs
+    Replay,
+}
+
+impl BackendKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "python" | "" => Some(BackendKind::Python),
+            "mock" => Some(BackendKind::Mock),
+            "replay" => Some(BackendKind::Replay),
+            _ => None,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        match std::env::var("MOODPULSE_EMOTION_BACKEND") {
+            Ok(value) => BackendKind::parse(&value).unwrap_or_else(|| {
+                eprintln!("Unknown emotion backend '{}', falling back to python", value);
+                BackendKind::Python
+            }),
+            Err(_) => BackendKind::Python,
+        }
+    }
+}
+
+impl EmotionBackend for EmotionService {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn start(&self) -> Result<(), StartError> {
+        EmotionService::start(self)
+    }
+
+    fn detect(&self, timeout: Option<Duration>) -> Result<String, DetectError> {
+        match timeout {
+            Some(timeout) => self.detect_emotion_with_timeout(timeout),
+            None => self.detect_emotion(),
+        }
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        EmotionService::stop(self)
+    }
+
+    fn health(&self) -> BackendHealth {
+        BackendHealth {
+            backend: self.name().to_string(),
+            status: self.status(),
+        }
+    }
+
+    fn logs(&self, limit: usize) -> Vec<LogLine> {
+        self.recent_logs(limit)
+    }
+
+    fn set_recording(&self, path: Option<PathBuf>) -> Result<(), String> {
+        EmotionService::set_recording(self, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_kind_parse() {
+        assert_eq!(BackendKind::parse("python"), Some(BackendKind::Python));
+        assert_eq!(BackendKind::parse(" Python "), Some(BackendKind::Python));
+        assert_eq!(BackendKind::parse("mock"), Some(BackendKind::Mock));
+        assert_eq!(BackendKind::parse("REPLAY"), Some(BackendKind::Replay));
+        assert_eq!(BackendKind::parse("tensorflow"), None);
+    }
+
+    #[test]
+    fn test_python_backend_health_reports_name() {
+        let backend: Box<dyn EmotionBackend> =
+            Box::new(EmotionService::new("python".to_string(), "missing.py".to_string()));
+        let health = backend.health();
+        assert_eq!(health.backend, "python");
+
+        let json = serde_json::to_value(&health).unwrap();
+        assert_eq!(json["state"], "stopped");
+    }
+}
@@ -1,90 +1,489 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
 use std::io::{BufRead, BufReader, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use std::thread;
+use std::thread::{self, JoinHandle};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+use crate::replay_backend::SessionRecorder;
+use crate::service_log::{self, LogLine, ServiceLog};
 
-pub struct EmotionService {
-    process: Arc<Mutex<Option<Child>>>,
+// Wire protocol version shared with emotion_service.py
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// Supervisor tuning
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+// A child that stays up this long resets the restart counter
+const STABLE_PERIOD: Duration = Duration::from_secs(120);
+
+// Python imports TensorFlow and loads models before it can answer hello
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(90);
+
+// Detection deadline, generous enough for DeepFace's first model load
+pub const DEFAULT_DETECT_TIMEOUT: Duration = Duration::from_secs(20);
+// Consecutive timeouts before the child is considered hung and restarted
+const MAX_CONSECUTIVE_TIMEOUTS: u32 = 3;
+
+/// Lifecycle state of the Python emotion service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ServiceState {
+    Starting,
+    Ready,
+    Crashed,
+    BackingOff,
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ServiceStatus {
+    pub state: ServiceState,
+    pub pid: Option<u32>,
+    pub restart_attempts: u32,
+    pub last_error: Option<String>,
+    pub service_info: Option<ServiceInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BackendAvailability {
+    pub fer: bool,
+    pub deepface: bool,
+}
+
+/// What emotion_service.py reports in its hello response
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ServiceInfo {
+    pub protocol_version: u32,
+    pub service_version: String,
+    pub backends: BackendAvailability,
+    pub camera_available: bool,
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+/// Why the service could not be brought up
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StartError {
+    /// The Python interpreter could not be launched
+    Spawn { message: String },
+    /// The script died or never answered hello
+    Handshake { message: String },
+    /// The script speaks a different protocol version
+    IncompatibleVersion { expected: u32, found: u32 },
+    /// FER failed to import, so detection cannot work
+    ModelsUnavailable { errors: Vec<String> },
+    /// The backend is misconfigured (missing replay file, bad scenario)
+    Config { message: String },
+}
+
+impl fmt::Display for StartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartError::Spawn { message } => write!(f, "{}", message),
+            StartError::Handshake { message } => write!(f, "Handshake failed: {}", message),
+            StartError::IncompatibleVersion { expected, found } => write!(
+                f,
+                "Incompatible emotion service protocol: expected v{}, script speaks v{}",
+                expected, found
+            ),
+            StartError::ModelsUnavailable { errors } => {
+                write!(f, "Emotion models failed to load: {}", errors.join("; "))
+            }
+            StartError::Config { message } => write!(f, "Invalid backend configuration: {}", message),
+        }
+    }
+}
+
+/// Decide whether a hello response describes a usable service
+fn check_service_info(info: &ServiceInfo) -> Result<(), StartError> {
+    if info.protocol_version != PROTOCOL_VERSION {
+        return Err(StartError::IncompatibleVersion {
+            expected: PROTOCOL_VERSION,
+            found: info.protocol_version,
+        });
+    }
+    // FER is the primary detector; DeepFace only refines its result
+    if !info.backends.fer {
+        let mut errors = info.errors.clone();
+        if errors.is_empty() {
+            errors.push("FER backend unavailable".to_string());
+        }
+        return Err(StartError::ModelsUnavailable { errors });
+    }
+    Ok(())
+}
+
+/// Why a detection request failed
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum DetectError {
+    /// The service is not running (crashed, backing off or stopped)
+    Unavailable(String),
+    /// No response arrived before the deadline
+    Timeout(String),
+    /// The pipe to the child broke mid-request
+    Io(String),
+    /// The service answered with an error instead of a result
+    Service(String),
+    /// The service could not open or read the camera
+    Camera(String),
+}
+
+impl fmt::Display for DetectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DetectError::Unavailable(msg) => write!(f, "Emotion service unavailable: {}", msg),
+            DetectError::Timeout(msg) => write!(f, "Emotion detector stalled: {}", msg),
+            DetectError::Io(msg) => write!(f, "Emotion service I/O error: {}", msg),
+            DetectError::Service(msg) => write!(f, "Emotion service error: {}", msg),
+            DetectError::Camera(msg) => write!(f, "Camera error: {}", msg),
+        }
+    }
+}
+
+/// One JSON line written to the child's stdin
+#[derive(Debug, Serialize)]
+struct Request<'a> {
+    v: u32,
+    id: u64,
+    cmd: &'a str,
+    params: Value,
+}
+
+/// One JSON line read from the child's stdout, matched to its request by `id`
+#[derive(Debug, Deserialize)]
+struct Response {
+    v: u32,
+    id: Option<u64>,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<String>,
+    /// Machine-readable class of `error`, e.g. "camera"
+    #[serde(default)]
+    code: Option<String>,
+}
+
+/// Parse a stdout line, discarding anything that is not a protocol frame
+/// (stray prints from FER, DeepFace or TensorFlow)
+fn parse_response(line: &str) -> Option<Response> {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        return None;
+    }
+    serde_json::from_str(line).ok()
+}
+
+/// Reply channels of the requests still waiting for a response, by id.
+/// `None` once the child closed stdout, so no response can arrive any more.
+type Pending = Arc<Mutex<Option<HashMap<u64, Sender<Response>>>>>;
+
+/// A running child plus the requests its stdout reader thread answers
+struct ServiceProcess {
+    child: Child,
+    pending: Pending,
+    next_id: u64,
+    consecutive_timeouts: u32,
+}
+
+impl ServiceProcess {
+    /// Write one request. The response is awaited on the returned `Reply`,
+    /// which does not need the process, so callers can release its lock.
+    fn send(&mut self, cmd: &str, params: Value) -> Result<Reply, String> {
+        self.next_id += 1;
+        let request = Request {
+            v: PROTOCOL_VERSION,
+            id: self.next_id,
+            cmd,
+            params,
+        };
+        let mut frame = serde_json::to_string(&request)
+            .map_err(|e| format!("Failed to encode request: {}", e))?;
+        frame.push('\n');
+
+        // Register before writing so even an instant answer finds its caller
+        let (sender, receiver) = mpsc::channel();
+        {
+            let mut pending = self.pending.lock()
+                .map_err(|e| format!("Pending requests lock poisoned: {}", e))?;
+            pending.as_mut()
+                .ok_or_else(|| "Python process closed stdout".to_string())?
+                .insert(self.next_id, sender);
+        }
+        let reply = Reply { id: self.next_id, receiver, pending: Arc::clone(&self.pending) };
+
+        let stdin = self.child.stdin.as_mut()
+            .ok_or_else(|| "Python stdin not available".to_string())?;
+        stdin.write_all(frame.as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(|e| format!("Failed to write to Python stdin: {}", e))?;
+
+        Ok(reply)
+    }
+}
+
+/// The pending response to one request. Dropping it unregisters the
+/// request, so an answer arriving after a timeout is discarded.
+struct Reply {
+    id: u64,
+    receiver: Receiver<Response>,
+    pending: Pending,
+}
+
+impl Reply {
+    fn wait(&self, timeout: Duration) -> Result<Response, RecvTimeoutError> {
+        self.receiver.recv_timeout(timeout)
+    }
+}
+
+impl Drop for Reply {
+    fn drop(&mut self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            if let Some(pending) = pending.as_mut() {
+                pending.remove(&self.id);
+            }
+        }
+    }
+}
+
+/// Hand a response to the request waiting for it, if any still is
+fn route_response(pending: &Pending, response: Response) {
+    let sender = match (pending.lock(), response.id) {
+        (Ok(mut pending), Some(id)) => pending.as_mut().and_then(|p| p.remove(&id)),
+        _ => None,
+    };
+    match sender {
+        Some(sender) => {
+            let _ = sender.send(response);
+        }
+        None => eprintln!("Discarding emotion service response for request {:?}", response.id),
+    }
+}
+
+struct Inner {
+    process: Mutex<Option<ServiceProcess>>,
+    status: Mutex<ServiceStatus>,
+    stopping: AtomicBool,
+    detect_timeout_ms: AtomicU64,
+    log: Arc<ServiceLog>,
+    recorder: Mutex<Option<SessionRecorder>>,
     python_path: String,
     script_path: String,
 }
 
+pub struct EmotionService {
+    inner: Arc<Inner>,
+    supervisor: Mutex<Option<JoinHandle<()>>>,
+}
+
 impl EmotionService {
     pub fn new(python_path: String, script_path: String) -> Self {
         EmotionService {
-            process: Arc::new(Mutex::new(None)),
-            python_path,
-            script_path,
+            inner: Arc::new(Inner {
+                process: Mutex::new(None),
+                status: Mutex::new(ServiceStatus {
+                    state: ServiceState::Stopped,
+                    pid: None,
+                    restart_attempts: 0,
+                    last_error: None,
+                    service_info: None,
+                }),
+                stopping: AtomicBool::new(false),
+                detect_timeout_ms: AtomicU64::new(DEFAULT_DETECT_TIMEOUT.as_millis() as u64),
+                log: Arc::new(ServiceLog::new(service_log::DEFAULT_CAPACITY)),
+                recorder: Mutex::new(None),
+                python_path,
+                script_path,
+            }),
+            supervisor: Mutex::new(None),
         }
     }
 
-    pub fn start(&self) -> Result<(), String> {
-        let mut process_guard = self.process.lock()
-            .map_err(|e| format!("Handle lock poisoned: {}", e))?;
+    pub fn start(&self) -> Result<(), StartError> {
+        {
+            let process_guard = self.inner.process.lock()
+                .map_err(|e| StartError::Spawn { message: format!("Handle lock poisoned: {}", e) })?;
 
-        if process_guard.is_some() {
-            return Ok(());
+            if process_guard.is_some() {
+                return Ok(());
+            }
         }
 
-        println!("Starting Python emotion service...");
+        self.inner.stopping.store(false, Ordering::SeqCst);
+        self.inner.set_state(ServiceState::Starting, None);
 
-        let child = Command::new(&self.python_path)
-            .arg(&self.script_path)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to start Python process: {}", e))?;
+        // Launch without holding the process lock, so detect, status and
+        // stop stay responsive for the whole handshake
+        let (process, info) = match self.inner.launch() {
+            Ok(launched) => launched,
+            Err(e) => {
+                self.inner.set_state(ServiceState::Crashed, Some(e.to_string()));
+                return Err(e);
+            }
+        };
+        if self.inner.install(process, info) {
+            // A manual (re)start gets the supervisor a fresh set of attempts
+            self.inner.set_restart_attempts(0);
+        } else if self.inner.stopping.load(Ordering::SeqCst) {
+            return Err(StartError::Handshake { message: "service stopped during startup".to_string() });
+        }
 
-        println!("Python emotion service started with PID: {:?}", child.id());
-        *process_guard = Some(child);
+        let mut supervisor_guard = self.supervisor.lock()
+            .map_err(|e| StartError::Spawn { message: format!("Supervisor lock poisoned: {}", e) })?;
+        // Respawn the supervisor if it gave up after too many restarts
+        let needs_supervisor = match supervisor_guard.as_ref() {
+            Some(handle) => handle.is_finished(),
+            None => true,
+        };
+        if needs_supervisor {
+            let inner = Arc::clone(&self.inner);
+            *supervisor_guard = Some(thread::spawn(move || supervise(inner)));
+        }
 
         Ok(())
     }
 
-    pub fn detect_emotion(&self) -> Result<String, String> {
-        let mut process_guard = self.process.lock()
-            .map_err(|e| format!("Handle lock poisoned: {}", e))?;
+    pub fn status(&self) -> ServiceStatus {
+        self.inner.status.lock()
+            .map(|s| s.clone())
+            .unwrap_or_else(|e| e.into_inner().clone())
+    }
 
-        let process = process_guard.as_mut()
-            .ok_or_else(|| "Python process not started".to_string())?;
+    /// Mirror the child's stderr into a rotating log file under `dir`
+    pub fn set_log_dir(&self, dir: PathBuf) -> Result<(), String> {
+        self.inner.log.set_dir(dir)
+    }
 
-        // Write command to stdin
-        if let Some(stdin) = process.stdin.as_mut() {
-            stdin.write_all(b"DETECT\n")
-                .map_err(|e| format!("Failed to write to Python stdin: {}", e))?;
-            stdin.flush()
-                .map_err(|e| format!("Failed to flush Python stdin: {}", e))?;
-        } else {
-            return Err("Python stdin not available".to_string());
+    /// The most recent stderr (and stray stdout) lines, oldest first
+    pub fn recent_logs(&self, limit: usize) -> Vec<LogLine> {
+        self.inner.log.recent(limit)
+    }
+
+    /// Append every raw detection response to `path` (JSONL, replayable
+    /// with ReplayBackend); `None` stops recording
+    pub fn set_recording(&self, path: Option<PathBuf>) -> Result<(), String> {
+        let recorder = match path {
+            Some(path) => Some(SessionRecorder::create(path)?),
+            None => None,
+        };
+        let mut recorder_guard = self.inner.recorder.lock()
+            .map_err(|e| format!("Recorder lock poisoned: {}", e))?;
+        if let Some(recorder) = &recorder {
+            println!("Recording emotion service responses to {}", recorder.path().display());
         }
+        *recorder_guard = recorder;
+        Ok(())
+    }
+
+    pub fn detect_timeout(&self) -> Duration {
+        Duration::from_millis(self.inner.detect_timeout_ms.load(Ordering::SeqCst))
+    }
 
-        // Read response from stdout
-        if let Some(stdout) = process.stdout.as_mut() {
-            let mut reader = BufReader::new(stdout);
-            let mut line = String::new();
-            reader.read_line(&mut line)
-                .map_err(|e| format!("Failed to read from Python stdout: {}", e))?;
+    pub fn set_detect_timeout(&self, timeout: Duration) {
+        self.inner.detect_timeout_ms.store(timeout.as_millis() as u64, Ordering::SeqCst);
+    }
 
-            Ok(line.trim().to_string())
-        } else {
-            Err("Python stdout not available".to_string())
+    pub fn detect_emotion(&self) -> Result<String, DetectError> {
+        self.detect_emotion_with_timeout(self.detect_timeout())
+    }
+
+    pub fn detect_emotion_with_timeout(&self, timeout: Duration) -> Result<String, DetectError> {
+        let (pid, reply) = {
+            let mut process_guard = self.inner.process.lock()
+                .map_err(|e| DetectError::Unavailable(format!("Handle lock poisoned: {}", e)))?;
+
+            let process = process_guard.as_mut()
+                .ok_or_else(|| DetectError::Unavailable(format!("Python process not running ({:?})", self.status().state)))?;
+
+            match process.send("detect", Value::Object(Default::default())) {
+                Ok(reply) => (process.child.id(), reply),
+                Err(message) => {
+                    self.inner.mark_crashed(&mut process.child, &message);
+                    return Err(DetectError::Io(message));
+                }
+            }
+        };
+
+        // Wait for the reader thread to hand us the matching response,
+        // without the process lock so stop and restart are not held up
+        match reply.wait(timeout) {
+            Ok(response) => {
+                self.inner.with_process(pid, |process| process.consecutive_timeouts = 0);
+                if let Ok(mut recorder_guard) = self.inner.recorder.lock() {
+                    if let Some(recorder) = recorder_guard.as_mut() {
+                        recorder.record(
+                            response.result.as_ref(),
+                            response.error.as_deref(),
+                            response.code.as_deref(),
+                        );
+                    }
+                }
+                if response.v != PROTOCOL_VERSION {
+                    return Err(DetectError::Service(format!(
+                        "protocol version mismatch: expected {}, got {}",
+                        PROTOCOL_VERSION, response.v
+                    )));
+                }
+                if let Some(error) = response.error {
+                    return Err(match response.code.as_deref() {
+                        Some("camera") => DetectError::Camera(error),
+                        _ => DetectError::Service(error),
+                    });
+                }
+                let result = response.result.unwrap_or(Value::Null);
+                Ok(result.to_string())
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                self.inner.with_process(pid, |process| {
+                    process.consecutive_timeouts += 1;
+                    if process.consecutive_timeouts >= MAX_CONSECUTIVE_TIMEOUTS {
+                        let message = format!("{} consecutive detection timeouts", process.consecutive_timeouts);
+                        self.inner.mark_crashed(&mut process.child, &message);
+                    }
+                });
+                Err(DetectError::Timeout(format!("no response within {:?}", timeout)))
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                let message = "Python process closed stdout".to_string();
+                self.inner.with_process(pid, |process| self.inner.mark_crashed(&mut process.child, &message));
+                Err(DetectError::Io(message))
+            }
         }
     }
 
     pub fn stop(&self) -> Result<(), String> {
-        let mut process_guard = self.process.lock()
+        self.inner.stopping.store(true, Ordering::SeqCst);
+
+        // Wait for the supervisor so it cannot respawn the child behind our back
+        if let Ok(mut supervisor_guard) = self.supervisor.lock() {
+            if let Some(handle) = supervisor_guard.take() {
+                let _ = handle.join();
+            }
+        }
+
+        let mut process_guard = self.inner.process.lock()
             .map_err(|e| format!("Handle lock poisoned: {}", e))?;
 
-        if let Some(mut process) = process_guard.take() {
+        if let Some(mut service_process) = process_guard.take() {
             println!("Stopping Python emotion service...");
 
-            // Try to send QUIT command
-            if let Some(stdin) = process.stdin.as_mut() {
-                let _ = stdin.write_all(b"QUIT\n");
-                let _ = stdin.flush();
-            }
+            // Try to send quit command
+            let _ = service_process.send("quit", Value::Object(Default::default()));
+            let mut process = service_process.child;
 
             // Wait for process to exit with timeout using try_wait
             let timeout = Duration::from_secs(5);
@@ -123,6 +522,8 @@ impl EmotionService {
             }
         }
 
+        self.inner.set_state(ServiceState::Stopped, None);
+
         Ok(())
     }
 }
@@ -132,3 +533,528 @@ impl Drop for EmotionService {
         let _ = self.stop();
     }
 }
+
+impl Inner {
+    /// Spawn the child and complete the hello handshake
+    fn launch(&self) -> Result<(ServiceProcess, ServiceInfo), StartError> {
+        let mut process = self.spawn_child()
+            .map_err(|message| StartError::Spawn { message })?;
+
+        match handshake(&mut process, &self.stopping) {
+            Ok(info) => {
+                println!(
+                    "Emotion service v{} ready (protocol v{}, fer: {}, deepface: {}, camera: {})",
+                    info.service_version,
+                    info.protocol_version,
+                    info.backends.fer,
+                    info.backends.deepface,
+                    info.camera_available
+                );
+                for error in &info.errors {
+                    eprintln!("Emotion service warning: {}", error);
+                }
+                Ok((process, info))
+            }
+            Err(e) => {
+                let _ = process.child.kill();
+                let _ = process.child.wait();
+                Err(e)
+            }
+        }
+    }
+
+    fn spawn_child(&self) -> Result<ServiceProcess, String> {
+        println!("Starting Python emotion service...");
+
+        let mut child = Command::new(&self.python_path)
+            .arg(&self.script_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start Python process: {}", e))?;
+
+        let pid = child.id();
+        println!("Python emotion service started with PID: {:?}", pid);
+
+        // Drain stderr continuously so a full pipe never blocks the child
+        if let Some(stderr) = child.stderr.take() {
+            let log = Arc::clone(&self.log);
+            thread::spawn(move || {
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    log.push("stderr", Some(pid), &line);
+                }
+            });
+        }
+
+        // Read stdout on a dedicated thread so a stalled child never blocks the caller.
+        // The thread ends at EOF, which disconnects the channel.
+        let stdout = child.stdout.take()
+            .ok_or_else(|| "Python stdout not available".to_string())?;
+        let pending: Pending = Arc::new(Mutex::new(Some(HashMap::new())));
+        let routes = Arc::clone(&pending);
+        let log = Arc::clone(&self.log);
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                match parse_response(&line) {
+                    Some(response) => route_response(&routes, response),
+                    None => {
+                        if !line.trim().is_empty() {
+                            log.push("stdout", Some(pid), &line);
+                        }
+                    }
+                }
+            }
+            // Dropping the senders wakes every waiting request with Disconnected
+            if let Ok(mut routes) = routes.lock() {
+                *routes = None;
+            }
+        });
+
+        Ok(ServiceProcess {
+            child,
+            pending,
+            next_id: 0,
+            consecutive_timeouts: 0,
+        })
+    }
+
+    fn set_state(&self, state: ServiceState, error: Option<String>) {
+        if let Ok(mut status) = self.status.lock() {
+            status.state = state;
+            if state != ServiceState::Ready {
+                status.pid = None;
+            }
+            if error.is_some() {
+                status.last_error = error;
+            }
+        }
+    }
+
+    fn set_ready(&self, pid: u32, info: ServiceInfo) {
+        if let Ok(mut status) = self.status.lock() {
+            status.state = ServiceState::Ready;
+            status.pid = Some(pid);
+            status.service_info = Some(info);
+        }
+    }
+
+    /// Make a launched child the running one, unless a stop was requested or
+    /// another launch got there first. Returns whether it was installed.
+    fn install(&self, mut process: ServiceProcess, info: ServiceInfo) -> bool {
+        let mut process_guard = match self.process.lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                let _ = process.child.kill();
+                let _ = process.child.wait();
+                return false;
+            }
+        };
+        if self.stopping.load(Ordering::SeqCst) || process_guard.is_some() {
+            let _ = process.child.kill();
+            let _ = process.child.wait();
+            return false;
+        }
+        self.set_ready(process.child.id(), info);
+        *process_guard = Some(process);
+        true
+    }
+
+    /// Run `f` on the running child if it is still the one with `pid`; it
+    /// may have been stopped or replaced while a request was in flight
+    fn with_process(&self, pid: u32, f: impl FnOnce(&mut ServiceProcess)) {
+        if let Ok(mut process_guard) = self.process.lock() {
+            if let Some(process) = process_guard.as_mut().filter(|p| p.child.id() == pid) {
+                f(process);
+            }
+        }
+    }
+
+    fn restart_attempts(&self) -> u32 {
+        self.status.lock().map(|s| s.restart_attempts).unwrap_or(0)
+    }
+
+    fn set_restart_attempts(&self, attempts: u32) {
+        if let Ok(mut status) = self.status.lock() {
+            status.restart_attempts = attempts;
+        }
+    }
+
+    /// Kill a child that stopped talking so the supervisor picks it up on its next poll
+    fn mark_crashed(&self, process: &mut Child, message: &str) {
+        eprintln!("Emotion service failure: {}", message);
+        let _ = process.kill();
+        self.set_state(ServiceState::Crashed, Some(message.to_string()));
+    }
+
+    /// Sleep for `duration`, waking early when a stop was requested.
+    /// Returns false if the service is stopping.
+    fn sleep_unless_stopping(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        while Instant::now() < deadline {
+            if self.stopping.load(Ordering::SeqCst) {
+                return false;
+            }
+            thread::sleep(POLL_INTERVAL.min(deadline - Instant::now()));
+        }
+        !self.stopping.load(Ordering::SeqCst)
+    }
+}
+
+/// Send hello and validate what the script reports about itself.
+/// Gives up early once `stopping` is set so `stop` never waits out the handshake.
+fn handshake(process: &mut ServiceProcess, stopping: &AtomicBool) -> Result<ServiceInfo, StartError> {
+    let reply = process.send("hello", Value::Object(Default::default()))
+        .map_err(|message| StartError::Handshake { message })?;
+
+    let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+    let response = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match reply.wait(POLL_INTERVAL.min(remaining)) {
+            Ok(response) => break response,
+            Err(RecvTimeoutError::Timeout) if stopping.load(Ordering::SeqCst) => {
+                return Err(StartError::Handshake { message: "service stopped during startup".to_string() });
+            }
+            Err(RecvTimeoutError::Timeout) if remaining > POLL_INTERVAL => {}
+            Err(RecvTimeoutError::Timeout) => {
+                return Err(StartError::Handshake {
+                    message: format!("no hello response within {:?}", HANDSHAKE_TIMEOUT),
+                });
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(StartError::Handshake { message: "Python process exited during startup".to_string() });
+            }
+        }
+    };
+
+    if let Some(error) = response.error {
+        // A script that rejects our frame version reports it here
+        return Err(if response.v != PROTOCOL_VERSION {
+            StartError::IncompatibleVersion { expected: PROTOCOL_VERSION, found: response.v }
+        } else {
+            StartError::Handshake { message: error }
+        });
+    }
+
+    let info: ServiceInfo = serde_json::from_value(response.result.unwrap_or(Value::Null))
+        .map_err(|e| StartError::Handshake { message: format!("Invalid hello response: {}", e) })?;
+    check_service_info(&info)?;
+    Ok(info)
+}
+
+/// Exponential backoff: 1s, 2s, 4s, ... capped at MAX_BACKOFF
+fn backoff_delay(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .checked_mul(1u32 << attempt.min(16))
+        .unwrap_or(MAX_BACKOFF)
+        .min(MAX_BACKOFF)
+}
+
+/// Watch the child process and restart it with backoff when it dies. The
+/// attempt count lives in the status so a manual start can reset it.
+fn supervise(inner: Arc<Inner>) {
+    let mut running_since = Instant::now();
+
+    while inner.sleep_unless_stopping(POLL_INTERVAL) {
+        let exit_reason = {
+            let mut process_guard = match inner.process.lock() {
+                Ok(guard) => guard,
+                Err(_) => break,
+            };
+
+            let reason = match process_guard.as_mut() {
+                Some(process) => match process.child.try_wait() {
+                    Ok(Some(status)) => Some(format!("Python process exited with status: {}", status)),
+                    Ok(None) => None,
+                    Err(e) => Some(format!("Error checking Python process status: {}", e)),
+                },
+                None => Some("Python process not running".to_string()),
+            };
+
+            if reason.is_some() {
+                if let Some(mut process) = process_guard.take() {
+                    let _ = process.child.kill();
+                    let _ = process.child.wait();
+                }
+            }
+            reason
+        };
+
+        let reason = match exit_reason {
+            Some(reason) => reason,
+            None => {
+                if running_since.elapsed() >= STABLE_PERIOD && inner.restart_attempts() > 0 {
+                    inner.set_restart_attempts(0);
+                }
+                continue;
+            }
+        };
+
+        if inner.stopping.load(Ordering::SeqCst) {
+            break;
+        }
+
+        eprintln!("Emotion service crashed: {}", reason);
+        inner.set_state(ServiceState::Crashed, Some(reason));
+
+        let attempts = inner.restart_attempts();
+        if attempts >= MAX_RESTART_ATTEMPTS {
+            eprintln!("Emotion service restart limit ({}) reached, giving up", MAX_RESTART_ATTEMPTS);
+            break;
+        }
+
+        let delay = backoff_delay(attempts);
+        inner.set_restart_attempts(attempts + 1);
+        inner.set_state(ServiceState::BackingOff, None);
+        println!("Restarting emotion service in {:?} (attempt {}/{})", delay, attempts + 1, MAX_RESTART_ATTEMPTS);
+
+        if !inner.sleep_unless_stopping(delay) {
+            break;
+        }
+
+        // Launch without holding the process lock; the handshake can take a while
+        inner.set_state(ServiceState::Starting, None);
+        match inner.launch() {
+            Ok((process, info)) => {
+                if !inner.install(process, info) && inner.stopping.load(Ordering::SeqCst) {
+                    break;
+                }
+                running_since = Instant::now();
+            }
+            Err(_) if inner.stopping.load(Ordering::SeqCst) => break,
+            Err(e) => {
+                // Leave the slot empty; the next poll counts this as another crash
+                eprintln!("{}", e);
+                inner.set_state(ServiceState::Crashed, Some(e.to_string()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay_doubles_and_caps() {
+        assert_eq!(backoff_delay(0), Duration::from_secs(1));
+        assert_eq!(backoff_delay(1), Duration::from_secs(2));
+        assert_eq!(backoff_delay(3), Duration::from_secs(8));
+        assert_eq!(backoff_delay(10), MAX_BACKOFF);
+        assert_eq!(backoff_delay(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_new_service_is_stopped() {
+        let service = EmotionService::new("python".to_string(), "missing.py".to_string());
+        let status = service.status();
+        assert_eq!(status.state, ServiceState::Stopped);
+        assert_eq!(status.restart_attempts, 0);
+    }
+
+    #[test]
+    fn test_parse_response_skips_noise() {
+        assert!(parse_response("").is_none());
+        assert!(parse_response("1/1 [==============================] - 0s 45ms/step").is_none());
+        assert!(parse_response("{not json").is_none());
+
+        let response = parse_response(r#"{"v":1,"id":7,"result":{"has_face":true}}"#)
+            .expect("valid frame");
+        assert_eq!(response.id, Some(7));
+        assert_eq!(response.result.unwrap()["has_face"], Value::Bool(true));
+        assert!(response.error.is_none());
+    }
+
+    #[test]
+    fn test_detect_on_stopped_service_is_unavailable() {
+        let service = EmotionService::new("python".to_string(), "missing.py".to_string());
+        match service.detect_emotion() {
+            Err(DetectError::Unavailable(_)) => {}
+            other => panic!("expected Unavailable, got {:?}", other),
+        }
+    }
+
+    const GOOD_HELLO: &str = r#"{"protocol_version":1,"service_version":"test","backends":{"fer":true,"deepface":false},"camera_available":false,"errors":[]}"#;
+
+    /// A script path in a directory of its own for this process and test, so
+    /// parallel runs and other checkouts never share a fixture
+    #[cfg(unix)]
+    fn script_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("moodpulse_{}_{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("service.sh")
+    }
+
+    #[cfg(unix)]
+    fn remove_script(script: &std::path::Path) {
+        if let Some(dir) = script.parent() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+
+    /// Write a shell script that answers hello with `hello` and runs `on_detect`
+    /// for every other request, with the request id in `$id`
+    #[cfg(unix)]
+    fn fake_service(name: &str, hello: &str, on_detect: &str) -> (EmotionService, PathBuf) {
+        let script = script_path(name);
+        let body = r#"while read line; do
+  id=$(echo "$line" | sed 's/.*"id":\([0-9]*\).*/\1/')
+  case "$line" in
+    *quit*) exit 0;;
+    *hello*) printf '{"v":1,"id":%s,"result":%s}\n' "$id" 'HELLO_JSON';;
+    *) ON_DETECT;;
+  esac
+done
+"#
+        .replace("HELLO_JSON", hello)
+        .replace("ON_DETECT", on_detect);
+        std::fs::write(&script, body).unwrap();
+
+        let service = EmotionService::new("sh".to_string(), script.to_string_lossy().to_string());
+        (service, script)
+    }
+
+    #[test]
+    fn test_check_service_info_requires_fer() {
+        let mut info: ServiceInfo = serde_json::from_str(GOOD_HELLO).unwrap();
+        assert!(check_service_info(&info).is_ok());
+
+        info.backends.fer = false;
+        info.errors.push("No module named 'fer'".to_string());
+        match check_service_info(&info) {
+            Err(StartError::ModelsUnavailable { errors }) => assert_eq!(errors.len(), 1),
+            other => panic!("expected ModelsUnavailable, got {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_start_rejects_incompatible_version() {
+        let hello = GOOD_HELLO.replace(r#""protocol_version":1"#, r#""protocol_version":2"#);
+        let (service, script) = fake_service("future_service", &hello, ":");
+
+        match service.start() {
+            Err(StartError::IncompatibleVersion { expected: 1, found: 2 }) => {}
+            other => panic!("expected IncompatibleVersion, got {:?}", other),
+        }
+        assert_eq!(service.status().state, ServiceState::Crashed);
+
+        remove_script(&script);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_start_resets_restart_attempts() {
+        // As if the supervisor had given up before the user restarted the service
+        let (service, script) = fake_service("restarted_service", GOOD_HELLO, ":");
+        service.inner.set_restart_attempts(MAX_RESTART_ATTEMPTS);
+
+        service.start().expect("Failed to start sh");
+        assert_eq!(service.status().restart_attempts, 0);
+
+        service.stop().unwrap();
+        remove_script(&script);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stop_interrupts_handshake() {
+        // A child that never answers hello, like Python still importing TensorFlow
+        let script = script_path("mute_service");
+        std::fs::write(&script, "while read line; do :; done\n").unwrap();
+        let service = Arc::new(EmotionService::new("sh".to_string(), script.to_string_lossy().to_string()));
+
+        let starting = Arc::clone(&service);
+        let start = thread::spawn(move || starting.start());
+        thread::sleep(Duration::from_millis(200));
+
+        let started = Instant::now();
+        service.stop().unwrap();
+        match start.join().unwrap() {
+            Err(StartError::Handshake { .. }) => {}
+            other => panic!("expected Handshake, got {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        remove_script(&script);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stop_does_not_wait_for_a_pending_detection() {
+        let (service, script) = fake_service("slow_service", GOOD_HELLO, ":");
+        let service = Arc::new(service);
+        service.start().expect("Failed to start sh");
+
+        let detecting = Arc::clone(&service);
+        let detect = thread::spawn(move || detecting.detect_emotion_with_timeout(Duration::from_secs(10)));
+        thread::sleep(Duration::from_millis(200));
+
+        let started = Instant::now();
+        service.stop().unwrap();
+        match detect.join().unwrap() {
+            Err(DetectError::Io(_)) => {}
+            other => panic!("expected Io, got {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        remove_script(&script);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_matches_response_by_id() {
+        // Prints noise and a stale frame before answering with the request's own id
+        let (service, script) = fake_service(
+            "echo_service",
+            GOOD_HELLO,
+            r#"echo 'Downloading weights...'; printf '{"v":1,"id":999,"result":{"stale":true}}\n'; printf '{"v":1,"id":%s,"result":{"has_face":true}}\n' "$id""#,
+        );
+        service.start().expect("Failed to start sh");
+        assert_eq!(service.status().service_info.unwrap().service_version, "test");
+
+        let result = service.detect_emotion_with_timeout(Duration::from_secs(5))
+            .expect("detection should succeed");
+        let value: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(value["has_face"], Value::Bool(true));
+
+        service.stop().unwrap();
+        remove_script(&script);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_times_out_on_silent_child() {
+        // A child that completes the handshake but never answers a detection
+        let (service, script) = fake_service("silent_service", GOOD_HELLO, ":");
+        service.start().expect("Failed to start sh");
+        let started = Instant::now();
+        match service.detect_emotion_with_timeout(Duration::from_millis(200)) {
+            Err(DetectError::Timeout(_)) => {}
+            other => panic!("expected Timeout, got {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(service.status().state, ServiceState::Ready);
+
+        service.stop().unwrap();
+        remove_script(&script);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_camera_errors_are_classified() {
+        let (service, script) = fake_service(
+            "camera_service",
+            GOOD_HELLO,
+            r#"printf '{"v":1,"id":%s,"error":"Cannot open camera","code":"camera"}\n' "$id""#,
+        );
+        service.start().expect("Failed to start sh");
+        match service.detect_emotion_with_timeout(Duration::from_secs(5)) {
+            Err(DetectError::Camera(message)) => assert_eq!(message, "Cannot open camera"),
+            other => panic!("expected Camera, got {:?}", other),
+        }
+
+        service.stop().unwrap();
+        remove_script(&script);
+    }
+}
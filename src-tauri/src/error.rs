@@ -0,0 +1,135 @@
+use serde::Serialize;
+use std::fmt;
+use ts_rs::TS;
+
+use crate::db::MigrationError;
+use crate::emotion_service::DetectError;
+use crate::export::ExportError;
+
+/// The error every Tauri command returns. Serializes as
+/// `{"code": "backend_timeout", "message": "..."}` so the frontend can branch
+/// on `code` (and localize it) while `message` keeps the English detail.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(tag = "code", content = "message", rename_all = "snake_case")]
+pub enum MoodPulseError {
+    /// SQLite failed, or the database lock was poisoned
+    Database(String),
+    /// The emotion backend is not running or its pipe broke
+    BackendUnavailable(String),
+    /// The emotion backend did not answer before the deadline
+    BackendTimeout(String),
+    /// The camera could not be opened or read
+    Camera(String),
+    /// No audio output device, or the sound could not be played
+    AudioDevice(String),
+    Serialization(String),
+    /// A file could not be read or written
+    Io(String),
+    /// The caller passed an argument we cannot accept
+    Validation(String),
+}
+
+impl MoodPulseError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            MoodPulseError::Database(_) => "database",
+            MoodPulseError::BackendUnavailable(_) => "backend_unavailable",
+            MoodPulseError::BackendTimeout(_) => "backend_timeout",
+            MoodPulseError::Camera(_) => "camera",
+            MoodPulseError::AudioDevice(_) => "audio_device",
+            MoodPulseError::Serialization(_) => "serialization",
+            MoodPulseError::Io(_) => "io",
+            MoodPulseError::Validation(_) => "validation",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            MoodPulseError::Database(msg)
+            | MoodPulseError::BackendUnavailable(msg)
+            | MoodPulseError::BackendTimeout(msg)
+            | MoodPulseError::Camera(msg)
+            | MoodPulseError::AudioDevice(msg)
+            | MoodPulseError::Serialization(msg)
+            | MoodPulseError::Io(msg)
+            | MoodPulseError::Validation(msg) => msg,
+        }
+    }
+}
+
+impl fmt::Display for MoodPulseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code(), self.message())
+    }
+}
+
+impl std::error::Error for MoodPulseError {}
+
+impl From<rusqlite::Error> for MoodPulseError {
+    fn from(e: rusqlite::Error) -> Self {
+        MoodPulseError::Database(e.to_string())
+    }
+}
+
+impl From<MigrationError> for MoodPulseError {
+    fn from(e: MigrationError) -> Self {
+        MoodPulseError::Database(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for MoodPulseError {
+    fn from(e: serde_json::Error) -> Self {
+        MoodPulseError::Serialization(e.to_string())
+    }
+}
+
+impl From<DetectError> for MoodPulseError {
+    fn from(e: DetectError) -> Self {
+        let message = e.to_string();
+        match e {
+            DetectError::Timeout(_) => MoodPulseError::BackendTimeout(message),
+            DetectError::Camera(_) => MoodPulseError::Camera(message),
+            DetectError::Unavailable(_) | DetectError::Io(_) | DetectError::Service(_) => {
+                MoodPulseError::BackendUnavailable(message)
+            }
+        }
+    }
+}
+
+impl From<ExportError> for MoodPulseError {
+    fn from(e: ExportError) -> Self {
+        let message = e.to_string();
+        match e {
+            ExportError::UnknownField(_) => MoodPulseError::Validation(message),
+            ExportError::Io(_) => MoodPulseError::Io(message),
+            ExportError::Database(_) => MoodPulseError::Database(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let error = MoodPulseError::from(DetectError::Timeout("no response within 20s".to_string()));
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "backend_timeout");
+        assert_eq!(json["message"], "Emotion detector stalled: no response within 20s");
+        assert_eq!(json["code"], error.code());
+    }
+
+    #[test]
+    fn test_conversions_pick_the_right_kind() {
+        let camera = MoodPulseError::from(DetectError::Camera("Cannot open camera".to_string()));
+        assert_eq!(camera.code(), "camera");
+
+        let db = MoodPulseError::from(rusqlite::Error::QueryReturnedNoRows);
+        assert_eq!(db.code(), "database");
+
+        let bad_json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert_eq!(MoodPulseError::from(bad_json).code(), "serialization");
+    }
+}
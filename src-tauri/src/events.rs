@@ -0,0 +1,68 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use crate::detection::DetectionResult;
+use crate::sleep_timer::PlaybackStatus;
+use crate::emotion_analysis::{DayAnalyses, EmotionAnalysis, TimelinePoint};
+
+/// Every detection, whether or not it produced a record
+pub const EMOTION_DETECTED: &str = "emotion-detected";
+/// A detection failed; the payload is the DetectError
+pub const DETECTION_ERROR: &str = "emotion-detection-error";
+/// Today's analysis and timeline after a new record or check-in was stored
+pub const ANALYSIS_UPDATED: &str = "analysis-updated";
+/// Today's focus analysis after a new record was stored
+pub const FOCUS_CHANGED: &str = "focus-changed";
+/// Sent once when continuous work crosses the break threshold
+pub const BREAK_DUE: &str = "break-due";
+/// The sleep timer stopped ambient playback; the payload is the PlaybackStatus
+pub const SOUND_TIMER_FINISHED: &str = "sound-timer-finished";
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct AnalysisUpdate {
+    pub analysis: EmotionAnalysis,
+    pub timeline: Vec<TimelinePoint>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BreakDue {
+    pub work_minutes: f64,
+}
+
+fn emit<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if let Err(e) = app.emit_all(event, payload) {
+        eprintln!("Failed to emit {}: {}", event, e);
+    }
+}
+
+/// Push one detection to every window. `break_was_due` remembers the last
+/// alert state so break-due fires once per stretch.
+pub fn publish_detection(app: &AppHandle, detection: &DetectionResult, break_was_due: &AtomicBool) {
+    emit(app, EMOTION_DETECTED, detection.clone());
+
+    if detection.need_break_alert {
+        if !break_was_due.swap(true, Ordering::SeqCst) {
+            emit(app, BREAK_DUE, BreakDue { work_minutes: detection.work_minutes });
+        }
+    } else {
+        break_was_due.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Push today's analyses, computed from all of today's records and check-ins
+pub fn publish_analysis(app: &AppHandle, day: DayAnalyses) {
+    emit(app, ANALYSIS_UPDATED, AnalysisUpdate { analysis: day.analysis, timeline: day.timeline });
+    emit(app, FOCUS_CHANGED, day.focus);
+}
+
+pub fn publish_error<S: Serialize + Clone>(app: &AppHandle, error: S) {
+    emit(app, DETECTION_ERROR, error);
+}
+
+pub fn publish_timer_finished(app: &AppHandle, status: PlaybackStatus) {
+    emit(app, SOUND_TIMER_FINISHED, status);
+}
@@ -0,0 +1,349 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use crate::db::EmotionRecord;
+
+/// Columns of `emotion_records` that can be exported, in their default order
+pub const FIELDS: [&str; 11] = [
+    "id",
+    "timestamp",
+    "datetime",
+    "fer_emotion",
+    "fer_confidence",
+    "deepface_emotion",
+    "deepface_confidence",
+    "mapped_emotion",
+    "work_minutes",
+    "is_away",
+    "has_face",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    /// One pretty-printed JSON array
+    Json,
+    /// One compact JSON object per line
+    JsonLines,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ExportSummary {
+    pub path: String,
+    pub rows: usize,
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    /// A requested field is not an `emotion_records` column
+    UnknownField(String),
+    Io(std::io::Error),
+    Database(rusqlite::Error),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::UnknownField(field) => {
+                write!(f, "Unknown field '{}', expected any of {}", field, FIELDS.join(", "))
+            }
+            ExportError::Io(e) => write!(f, "Export failed: {}", e),
+            ExportError::Database(e) => write!(f, "Export failed reading records: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+impl From<csv::Error> for ExportError {
+    fn from(e: csv::Error) -> Self {
+        ExportError::Io(e.into())
+    }
+}
+
+impl From<serde_json::Error> for ExportError {
+    fn from(e: serde_json::Error) -> Self {
+        ExportError::Io(e.into())
+    }
+}
+
+impl From<rusqlite::Error> for ExportError {
+    fn from(e: rusqlite::Error) -> Self {
+        ExportError::Database(e)
+    }
+}
+
+/// The fields to export, in the caller's order; every field when none are given
+pub fn resolve_fields(fields: Option<Vec<String>>) -> Result<Vec<&'static str>, ExportError> {
+    match fields {
+        None => Ok(FIELDS.to_vec()),
+        Some(fields) if fields.is_empty() => Ok(FIELDS.to_vec()),
+        Some(fields) => fields
+            .iter()
+            .map(|field| {
+                FIELDS.iter()
+                    .find(|known| *known == field)
+                    .copied()
+                    .ok_or_else(|| ExportError::UnknownField(field.clone()))
+            })
+            .collect(),
+    }
+}
+
+/// The selected fields of one record, in order
+fn select(record: &EmotionRecord, fields: &[&str]) -> Result<Vec<Value>, ExportError> {
+    let mut all = match serde_json::to_value(record)? {
+        Value::Object(map) => map,
+        _ => return Ok(Vec::new()),
+    };
+    Ok(fields.iter().map(|field| all.remove(*field).unwrap_or(Value::Null)).collect())
+}
+
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// One exported row as a JSON object, keeping the caller's field order
+struct Row<'a> {
+    fields: &'a [&'a str],
+    values: Vec<Value>,
+}
+
+impl Serialize for Row<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for (field, value) in self.fields.iter().zip(&self.values) {
+            map.serialize_entry(field, value)?;
+        }
+        map.end()
+    }
+}
+
+enum Sink {
+    Csv(Box<csv::Writer<BufWriter<File>>>),
+    Json { out: BufWriter<File>, empty: bool },
+    JsonLines(BufWriter<File>),
+}
+
+impl Sink {
+    fn open(file: File, format: ExportFormat, fields: &[&str]) -> Result<Self, ExportError> {
+        let out = BufWriter::new(file);
+        Ok(match format {
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(out);
+                writer.write_record(fields)?;
+                Sink::Csv(Box::new(writer))
+            }
+            ExportFormat::Json => Sink::Json { out, empty: true },
+            ExportFormat::JsonLines => Sink::JsonLines(out),
+        })
+    }
+
+    fn write(&mut self, fields: &[&str], record: &EmotionRecord) -> Result<(), ExportError> {
+        let values = select(record, fields)?;
+        match self {
+            Sink::Csv(writer) => writer.write_record(values.iter().map(csv_cell))?,
+            Sink::Json { out, empty } => {
+                out.write_all(if *empty { b"[\n  " } else { b",\n  " })?;
+                // Indent the object one level inside the array
+                let pretty = serde_json::to_string_pretty(&Row { fields, values })?;
+                out.write_all(pretty.replace('\n', "\n  ").as_bytes())?;
+                *empty = false;
+            }
+            Sink::JsonLines(out) => {
+                serde_json::to_writer(&mut *out, &Row { fields, values })?;
+                out.write_all(b"\n")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), ExportError> {
+        match self {
+            Sink::Csv(mut writer) => writer.flush()?,
+            Sink::Json { mut out, empty } => {
+                out.write_all(if empty { b"[]\n" } else { b"\n]\n" })?;
+                out.flush()?;
+            }
+            Sink::JsonLines(mut out) => out.flush()?,
+        }
+        Ok(())
+    }
+}
+
+/// Records `export_records` asks for at a time
+pub const BATCH_SIZE: usize = 500;
+
+/// Stream records to `path`, one batch at a time. `next_batch` returns the
+/// records after the (timestamp, id) of the last one written, or `None` at
+/// the start, and an empty batch once there are no more; callers that lock
+/// the database need hold it only for one batch.
+/// Rows go to a `.part` file that replaces `path` only once it is complete,
+/// so a failed export never leaves a truncated file behind.
+pub fn export_records<E, F>(path: &Path, format: ExportFormat, fields: &[&str], mut next_batch: F) -> Result<usize, E>
+where
+    E: From<ExportError>,
+    F: FnMut(Option<(i64, i64)>) -> Result<Vec<EmotionRecord>, E>,
+{
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+
+    let written = File::create(&partial).map_err(|e| E::from(e.into())).and_then(|file| {
+        let mut sink = Sink::open(file, format, fields)?;
+        let mut rows = 0;
+        let mut after = None;
+        loop {
+            let batch = next_batch(after)?;
+            let Some(last) = batch.last() else { break };
+            after = Some((last.timestamp, last.id.unwrap_or_default()));
+            for record in &batch {
+                sink.write(fields, record)?;
+            }
+            rows += batch.len();
+        }
+        sink.finish()?;
+        Ok(rows)
+    });
+
+    match written {
+        Ok(rows) => {
+            fs::rename(&partial, path).map_err(|e| E::from(e.into()))?;
+            Ok(rows)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    fn db_with_records() -> Database {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.init().unwrap();
+        for (i, emotion) in ["happy", "calm", "tired"].iter().enumerate() {
+            db.insert_record(&EmotionRecord {
+                id: None,
+                timestamp: 1735722000 + i as i64 * 60,
+                datetime: format!("2025-01-01 09:0{}:00", i),
+                fer_emotion: emotion.to_string(),
+                fer_confidence: 0.5,
+                deepface_emotion: if i == 0 { Some("happy".to_string()) } else { None },
+                deepface_confidence: if i == 0 { Some(0.25) } else { None },
+                mapped_emotion: emotion.to_string(),
+                work_minutes: i as f64,
+                is_away: false,
+                has_face: true,
+            }).unwrap();
+        }
+        db
+    }
+
+    /// Pages of two, so three records take several batches
+    fn export(db: &Database, path: &Path, format: ExportFormat, start: &str, end: &str, fields: &[&str]) -> Result<usize, ExportError> {
+        export_records(path, format, fields, |after| Ok(db.get_records_page(start, end, after, 2)?))
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("moodpulse_export_{}", name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_resolve_fields() {
+        assert_eq!(resolve_fields(None).unwrap().len(), FIELDS.len());
+        assert_eq!(
+            resolve_fields(Some(vec!["datetime".to_string(), "id".to_string()])).unwrap(),
+            ["datetime", "id"]
+        );
+        assert!(matches!(
+            resolve_fields(Some(vec!["password".to_string()])),
+            Err(ExportError::UnknownField(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_csv_with_selected_fields() {
+        let db = db_with_records();
+        let path = temp_path("fields.csv");
+        let fields = ["datetime", "mapped_emotion", "deepface_confidence"];
+
+        let rows = export(&db, &path, ExportFormat::Csv, "2025-01-01 09:00:00", "2025-01-01 09:01:00", &fields).unwrap();
+        assert_eq!(rows, 2);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "datetime,mapped_emotion,deepface_confidence\n\
+             2025-01-01 09:00:00,happy,0.25\n\
+             2025-01-01 09:01:00,calm,\n"
+        );
+    }
+
+    #[test]
+    fn test_export_json_formats_round_trip() {
+        let db = db_with_records();
+        let (start, end) = ("2025-01-01 00:00:00", "2025-01-01 23:59:59");
+
+        let json = temp_path("all.json");
+        assert_eq!(export(&db, &json, ExportFormat::Json, start, end, &FIELDS).unwrap(), 3);
+        let parsed: Vec<EmotionRecord> = serde_json::from_str(&fs::read_to_string(&json).unwrap()).unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[2].mapped_emotion, "tired");
+
+        let lines = temp_path("all.jsonl");
+        export(&db, &lines, ExportFormat::JsonLines, start, end, &["mapped_emotion", "id"]).unwrap();
+        let content = fs::read_to_string(&lines).unwrap();
+        assert_eq!(content.lines().count(), 3);
+        assert_eq!(content.lines().next().unwrap(), r#"{"mapped_emotion":"happy","id":1}"#);
+
+        let empty = temp_path("empty.json");
+        export(&db, &empty, ExportFormat::Json, "2030-01-01 00:00:00", "2030-01-02 00:00:00", &FIELDS).unwrap();
+        assert_eq!(fs::read_to_string(&empty).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn test_export_pages_past_equal_timestamps() {
+        let db = db_with_records();
+        let mut twin = db.get_records_page("2025-01-01 09:00:00", "2025-01-01 09:00:00", None, 1).unwrap().remove(0);
+        twin.id = None;
+        db.insert_record(&twin).unwrap();
+        let path = temp_path("twins.csv");
+
+        assert_eq!(export(&db, &path, ExportFormat::Csv, "0000-01-01 00:00:00", "9999-12-31 23:59:59", &["id"]).unwrap(), 4);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "id\n1\n4\n2\n3\n");
+    }
+
+    #[test]
+    fn test_failed_export_leaves_no_file() {
+        let db = db_with_records();
+        let path = std::env::temp_dir().join("moodpulse_missing_dir").join("out.csv");
+        assert!(matches!(
+            export(&db, &path, ExportFormat::Csv, "0000-01-01 00:00:00", "9999-12-31 23:59:59", &FIELDS),
+            Err(ExportError::Io(_))
+        ));
+        assert!(!path.exists());
+    }
+}
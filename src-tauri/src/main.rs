@@ -2,154 +2,620 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use tauri::{CustomMenuItem, SystemTray, SystemTrayMenu, SystemTrayEvent, Manager};
-use std::sync::Mutex;
-use chrono::Local;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+use chrono::{Local, NaiveDate, NaiveDateTime};
 
 mod audio;
-use audio::AudioPlayer;
+use audio::{AudioPlayer, Mixer};
+
+mod sounds;
+use sounds::{FadeSettings, MixerState, SoundInfo, SoundLibrary};
+
+mod sleep_timer;
+use sleep_timer::{PlaybackStatus, SleepTimer};
 
 mod db;
-use db::{Database, EmotionRecord};
+use db::{DailySummary, Database, EmotionEntry, EmotionRecord, EmotionRollup, MoodCheckin, SoundMix};
 
 mod emotion_service;
-use emotion_service::EmotionService;
+mod service_log;
+use service_log::LogLine;
+use emotion_service::{DetectError, EmotionService};
+
+mod emotion_backend;
+use emotion_backend::{BackendHealth, BackendKind, EmotionBackend};
+
+mod mock_backend;
+use mock_backend::{MockBackend, Scenario};
+
+mod replay_backend;
+use replay_backend::ReplayBackend;
+
+mod detection;
+use detection::{save_detection, DetectionResult};
+
+mod events;
+
+mod export;
+use export::{ExportFormat, ExportSummary};
+
+mod error;
+use error::MoodPulseError;
+
+mod scheduler;
+use scheduler::{IntervalScheduler, ScheduleStatus};
+
+mod daily_summary;
+
+mod retention;
+use retention::{RetentionPolicy, RetentionSummary};
 
 mod emotion_analysis;
-use emotion_analysis::{analyze_today_emotions, get_emotion_timeline, analyze_focus_time};
+use emotion_analysis::{
+    analyze_today_emotions, get_emotion_timeline, analyze_focus_time,
+    analyze_emotions_in_range, get_emotion_timeline_in_range, analyze_focus_time_in_range,
+    CheckinBlend, EmotionAnalysis, FocusAnalysis, PeriodReport, TimelinePoint,
+};
 
 #[cfg(test)]
 mod db_tests;
 
+#[cfg(test)]
+mod pipeline_tests;
+
 // Global state
 struct AppState {
     db: Mutex<Database>,
-    audio: Mutex<AudioPlayer>,
-    emotion_service: EmotionService,
+    audio: AudioPlayer,
+    emotion_backend: Box<dyn EmotionBackend>,
+    scheduler: IntervalScheduler,
+    // Whether the last detection asked for a break, so break-due fires once
+    break_due: AtomicBool,
+    // How much self-reported check-ins count against detections (0-1)
+    checkin_weight: Mutex<f64>,
+    retention: Mutex<RetentionPolicy>,
+    // Runs the retention pass at startup and then daily
+    retention_scheduler: IntervalScheduler,
+    // Expires the sleep timer whether or not any window is showing
+    sound_timer_scheduler: IntervalScheduler,
+}
+
+impl AppState {
+    fn db(&self) -> Result<MutexGuard<'_, Database>, MoodPulseError> {
+        self.db.lock()
+            .map_err(|e| MoodPulseError::Database(format!("Database lock poisoned: {}", e)))
+    }
+
+    /// Run `command` on the audio thread, which owns the output device
+    fn audio<R, F>(&self, command: F) -> Result<R, MoodPulseError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Mixer) -> R + Send + 'static,
+    {
+        self.audio.run(command).map_err(MoodPulseError::AudioDevice)
+    }
+
+    fn checkin_weight(&self) -> f64 {
+        // A plain f64 cannot be left half-written, so a poisoned lock is still usable
+        *self.checkin_weight.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn retention_policy(&self) -> RetentionPolicy {
+        *self.retention.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn apply_retention(&self) -> Result<RetentionSummary, MoodPulseError> {
+        let policy = self.retention_policy();
+        let weight = self.checkin_weight();
+        let now = Local::now();
+        let db = self.db()?;
+        // Summaries first: they outlive the raw records retention removes
+        let backfilled = daily_summary::backfill(&db, weight, now)?;
+        if backfilled > 0 {
+            println!("Backfilled {} daily summaries", backfilled);
+        }
+        let summary = retention::apply(&db, policy, now)?;
+        if summary.records_compacted > 0 {
+            println!(
+                "Retention compacted {} records into {} rollups",
+                summary.records_compacted, summary.rollups_written
+            );
+        }
+        Ok(summary)
+    }
+
+    /// Records and check-ins of the local dates `start..=end`
+    fn between(&self, start: NaiveDate, end: NaiveDate) -> Result<(Vec<EmotionRecord>, Vec<MoodCheckin>), MoodPulseError> {
+        let start = format!("{} 00:00:00", start.format("%Y-%m-%d"));
+        let end = format!("{} 23:59:59", end.format("%Y-%m-%d"));
+        let db = self.db()?;
+        Ok((db.get_records_by_date_range(&start, &end)?, db.get_checkins_by_date_range(&start, &end)?))
+    }
+
+    /// What is left of the local dates `start..=end` once retention has
+    /// compacted them: their daily summaries and half-hour rollups
+    fn compacted_between(&self, start: NaiveDate, end: NaiveDate) -> Result<(Vec<DailySummary>, Vec<EmotionRollup>), MoodPulseError> {
+        let (first, last) = (start.format("%Y-%m-%d").to_string(), end.format("%Y-%m-%d").to_string());
+        let db = self.db()?;
+        Ok((
+            db.get_daily_summaries(&first, &last)?,
+            db.get_rollups_by_date_range(&format!("{} 00:00:00", first), &format!("{} 23:59:59", last))?,
+        ))
+    }
+
+    /// Today's records and check-ins, for the blended analyses
+    fn today(&self) -> Result<(Vec<EmotionRecord>, Vec<MoodCheckin>), MoodPulseError> {
+        let db = self.db()?;
+        Ok((db.get_today_records()?, db.get_today_checkins()?))
+    }
+}
+
+/// Push today's analyses to every window after a record or check-in was
+/// added, and store them as today's summary. Both come from one load and
+/// one pass over today's data; the database is locked only to read and write.
+fn publish_today(app: &tauri::AppHandle, state: &AppState) {
+    let (records, checkins) = match state.today() {
+        Ok(today) => today,
+        Err(e) => {
+            eprintln!("Failed to load today's data: {}", e);
+            return;
+        }
+    };
+    let blend = CheckinBlend { checkins: &checkins, weight: state.checkin_weight() };
+    let now = Local::now();
+    let day = emotion_analysis::analyze_day(&now.format("%Y-%m-%d").to_string(), records, &blend, now.timestamp());
+    let stored = state.db().and_then(|db| Ok(db.upsert_daily_summary(&day.summary)?));
+    if let Err(e) = stored {
+        eprintln!("Failed to update today's summary: {}", e);
+    }
+    events::publish_analysis(app, day);
+}
+
+/// Detect once, persist the result and push it to every window;
+/// shared by the command and the scheduler
+fn run_detection(app: &tauri::AppHandle, state: &AppState, timeout: Option<Duration>) -> Result<String, DetectError> {
+    // Use the configured emotion backend, with an optional per-call deadline
+    let result = state.emotion_backend.detect(timeout)?;
+
+    // Parse and save to database, at the recorded time for replayed detections
+    let detection = DetectionResult::parse(&result);
+    let detected_at = match &detection {
+        Some(detection) => detection.detected_at(Local::now()),
+        None => Local::now(),
+    };
+    let saved = match state.db.lock() {
+        Ok(db) => match save_detection(&db, &result, detected_at) {
+            Ok(saved) => saved.is_some(),
+            Err(e) => {
+                eprintln!("DB insert error: {}", e);
+                false
+            }
+        },
+        Err(e) => {
+            eprintln!("Database lock poisoned: {}", e);
+            false
+        }
+    };
+
+    if let Some(detection) = detection {
+        events::publish_detection(app, &detection, &state.break_due);
+    }
+    if saved {
+        publish_today(app, state);
+    }
+
+    Ok(result)
+}
+
+/// Scheduler tick: detect and report failures to every window
+fn scheduled_detection(app: &tauri::AppHandle) {
+    let state = app.state::<AppState>();
+    if let Err(e) = run_detection(app, &state, None) {
+        eprintln!("Scheduled detection failed: {}", e);
+        events::publish_error(app, MoodPulseError::from(e));
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, MoodPulseError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| MoodPulseError::Validation(format!("Expected a date as YYYY-MM-DD, got '{}'", value)))
+}
+
+/// Parse a `start_date..=end_date` pair, rejecting a reversed range
+fn parse_date_range(start_date: &str, end_date: &str) -> Result<(NaiveDate, NaiveDate), MoodPulseError> {
+    let (start, end) = (parse_date(start_date)?, parse_date(end_date)?);
+    if start > end {
+        return Err(MoodPulseError::Validation(format!("{} is after {}", start_date, end_date)));
+    }
+    Ok((start, end))
+}
+
+/// One side of a `datetime BETWEEN` query: a full "YYYY-MM-DD HH:MM:SS", or a
+/// date taken at `time_of_day`
+fn parse_datetime_bound(value: &str, time_of_day: &str) -> Result<String, MoodPulseError> {
+    const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(value, FORMAT) {
+        return Ok(datetime.format(FORMAT).to_string());
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| MoodPulseError::Validation(
+        format!("Expected YYYY-MM-DD or YYYY-MM-DD HH:MM:SS, got '{}'", value)
+    ))?;
+    Ok(format!("{} {}", date.format("%Y-%m-%d"), time_of_day))
+}
+
+/// Like `parse_date_range` for the datetime-filtered queries; a bare date
+/// covers the whole day
+fn parse_datetime_range(start_date: &str, end_date: &str) -> Result<(String, String), MoodPulseError> {
+    let start = parse_datetime_bound(start_date, "00:00:00")?;
+    let end = parse_datetime_bound(end_date, "23:59:59")?;
+    if start > end {
+        return Err(MoodPulseError::Validation(format!("{} is after {}", start_date, end_date)));
+    }
+    Ok((start, end))
 }
 
 // Tauri commands
+/// Store emotion entries from the frontend (manual check-ins or imports);
+/// nothing is written unless every entry is valid
 #[tauri::command]
-fn save_emotion_data(_data: String) -> Result<String, String> {
-    Ok("Data saved successfully".to_string())
+fn save_emotion_data(state: tauri::State<AppState>, data: Vec<EmotionEntry>) -> Result<Vec<i64>, MoodPulseError> {
+    let mut entries = data;
+    for (i, entry) in entries.iter_mut().enumerate() {
+        entry.validate()
+            .map_err(|e| MoodPulseError::Validation(format!("Entry {}: {}", i, e)))?;
+    }
+    Ok(state.db()?.insert_entries(&entries)?)
 }
 
+/// Frontend entries in a datetime range (everything when omitted),
+/// optionally merged with the detected records
 #[tauri::command]
-fn load_emotion_data() -> Result<String, String> {
-    Ok("{}".to_string())
+fn load_emotion_data(
+    state: tauri::State<AppState>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    include_detected: Option<bool>
+) -> Result<Vec<EmotionEntry>, MoodPulseError> {
+    let (start, end) = parse_datetime_range(
+        start_date.as_deref().unwrap_or("0000-01-01 00:00:00"),
+        end_date.as_deref().unwrap_or("9999-12-31 23:59:59"),
+    )?;
+    let db = state.db()?;
+    if include_detected.unwrap_or(false) {
+        Ok(db.get_all_emotions_by_date_range(&start, &end)?)
+    } else {
+        Ok(db.get_entries_by_date_range(&start, &end)?)
+    }
 }
 
 #[tauri::command]
-fn detect_emotion(state: tauri::State<AppState>) -> Result<String, String> {
+fn detect_emotion(
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+    timeout_ms: Option<u64>
+) -> Result<DetectionResult, MoodPulseError> {
     println!("detect_emotion called");
+    let result = run_detection(&app, &state, timeout_ms.map(Duration::from_millis))?;
+    Ok(serde_json::from_str(&result)?)
+}
 
-    // Use the persistent emotion service
-    let result = state.emotion_service.detect_emotion()?;
-    println!("Python output: {}", result);
-
-    // Parse and save to database
-    if let Ok(data) = serde_json::from_str::<serde_json::Value>(&result) {
-        println!("JSON parsed successfully");
-        if let Some(emotions) = data["emotions"].as_array() {
-            println!("Found {} emotions", emotions.len());
-            if let Some(first_emotion) = emotions.first() {
-                println!("Processing emotion");
-                let now = Local::now();
-
-                // Get confidence values and normalize to 0-1 range
-                let fer_conf = first_emotion.get("confidence")
-                    .and_then(|v| v.as_f64())
-                    .unwrap_or(0.0);
-                let fer_confidence = if fer_conf > 1.0 { fer_conf / 100.0 } else { fer_conf };
-
-                let deepface_conf = emotions.get(1)
-                    .and_then(|e| e.get("confidence"))
-                    .and_then(|v| v.as_f64());
-                let deepface_confidence = deepface_conf.map(|c| if c > 1.0 { c / 100.0 } else { c });
-
-                let record = EmotionRecord {
-                    id: None,
-                    timestamp: now.timestamp(),
-                    datetime: now.format("%Y-%m-%d %H:%M:%S").to_string(),
-                    fer_emotion: first_emotion.get("emotion")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("calm").to_string(),
-                    fer_confidence,
-                    deepface_emotion: emotions.get(1)
-                        .and_then(|e| e.get("emotion"))
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string()),
-                    deepface_confidence,
-                    mapped_emotion: first_emotion.get("emotion")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("calm").to_string(),
-                    work_minutes: data["work_minutes"].as_f64().unwrap_or(0.0),
-                    is_away: data["is_away"].as_bool().unwrap_or(false),
-                    has_face: data["has_face"].as_bool().unwrap_or(false),
-                };
-
-                let db = state.db.lock()
-                    .map_err(|e| format!("Database lock poisoned: {}", e))?;
-                match db.insert_record(&record) {
-                    Ok(_) => println!("DB insert OK"),
-                    Err(e) => eprintln!("DB insert error: {}", e),
-                }
-            }
-        }
+/// Record a self-reported mood and refresh today's analyses everywhere
+#[tauri::command]
+fn record_mood_checkin(
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+    emotion: String,
+    intensity: u8,
+    note: Option<String>
+) -> Result<MoodCheckin, MoodPulseError> {
+    let mut checkin = MoodCheckin::new(emotion, intensity, note, Local::now());
+    checkin.validate().map_err(MoodPulseError::Validation)?;
+    checkin.id = Some(state.db()?.insert_checkin(&checkin)?);
+
+    publish_today(&app, &state);
+    Ok(checkin)
+}
+
+#[tauri::command]
+fn get_mood_checkins(
+    state: tauri::State<AppState>,
+    start_date: String,
+    end_date: String
+) -> Result<Vec<MoodCheckin>, MoodPulseError> {
+    let (start, end) = parse_datetime_range(&start_date, &end_date)?;
+    Ok(state.db()?.get_checkins_by_date_range(&start, &end)?)
+}
+
+#[tauri::command]
+fn get_checkin_weight(state: tauri::State<AppState>) -> f64 {
+    state.checkin_weight()
+}
+
+/// How much check-ins count in the emotion index and timeline; 0 ignores them
+#[tauri::command]
+fn set_checkin_weight(app: tauri::AppHandle, state: tauri::State<AppState>, weight: f64) -> Result<(), MoodPulseError> {
+    if !(0.0..=1.0).contains(&weight) {
+        return Err(MoodPulseError::Validation(format!("Check-in weight must be between 0 and 1, got {}", weight)));
+    }
+    *state.checkin_weight.lock().unwrap_or_else(PoisonError::into_inner) = weight;
+
+    publish_today(&app, &state);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_retention_policy(state: tauri::State<AppState>) -> RetentionPolicy {
+    state.retention_policy()
+}
+
+/// Change how many days of raw records are kept and compact right away
+#[tauri::command]
+fn set_retention_policy(state: tauri::State<AppState>, raw_days: u32) -> Result<RetentionSummary, MoodPulseError> {
+    *state.retention.lock().unwrap_or_else(PoisonError::into_inner) = RetentionPolicy { raw_days };
+    state.apply_retention()
+}
+
+/// Half-hour rollups of records that retention has compacted
+#[tauri::command]
+fn get_emotion_rollups(
+    state: tauri::State<AppState>,
+    start_date: String,
+    end_date: String
+) -> Result<Vec<EmotionRollup>, MoodPulseError> {
+    let (start, end) = parse_datetime_range(&start_date, &end_date)?;
+    Ok(state.db()?.get_rollups_by_date_range(&start, &end)?)
+}
+
+/// Per-day summaries for a calendar heatmap; days without data are absent
+#[tauri::command]
+fn get_daily_summaries(
+    state: tauri::State<AppState>,
+    start_date: String,
+    end_date: String
+) -> Result<Vec<DailySummary>, MoodPulseError> {
+    let (start, end) = parse_date_range(&start_date, &end_date)?;
+    Ok(state.db()?.get_daily_summaries(&start.format("%Y-%m-%d").to_string(), &end.format("%Y-%m-%d").to_string())?)
+}
+
+#[tauri::command]
+fn pause_detection(state: tauri::State<AppState>) {
+    state.scheduler.pause();
+}
+
+#[tauri::command]
+fn resume_detection(state: tauri::State<AppState>) {
+    state.scheduler.resume();
+}
+
+#[tauri::command]
+fn set_detection_interval(state: tauri::State<AppState>, interval_ms: u64) -> Result<(), MoodPulseError> {
+    state.scheduler.set_interval(Duration::from_millis(interval_ms))
+        .map_err(MoodPulseError::Validation)
+}
+
+#[tauri::command]
+fn get_detection_schedule(state: tauri::State<AppState>) -> ScheduleStatus {
+    state.scheduler.status()
+}
+
+#[tauri::command]
+fn get_emotion_service_status(state: tauri::State<AppState>) -> BackendHealth {
+    state.emotion_backend.health()
+}
+
+#[tauri::command]
+fn get_emotion_service_logs(state: tauri::State<AppState>, limit: Option<usize>) -> Vec<LogLine> {
+    state.emotion_backend.logs(limit.unwrap_or(service_log::DEFAULT_CAPACITY))
+}
+
+#[tauri::command]
+fn set_emotion_recording(state: tauri::State<AppState>, path: Option<String>) -> Result<(), MoodPulseError> {
+    state.emotion_backend.set_recording(path.map(PathBuf::from))
+        .map_err(MoodPulseError::Validation)
+}
+
+fn check_volume(volume: f32) -> Result<(), MoodPulseError> {
+    if (0.0..=1.0).contains(&volume) {
+        Ok(())
     } else {
-        println!("JSON parse failed");
+        Err(MoodPulseError::Validation(format!("Volume must be between 0 and 1, got {}", volume)))
     }
+}
 
-    Ok(result)
+#[tauri::command]
+fn list_sounds(state: tauri::State<AppState>) -> Result<Vec<SoundInfo>, MoodPulseError> {
+    state.audio(|mixer| mixer.sounds())
 }
 
 #[tauri::command]
-fn play_white_noise(state: tauri::State<AppState>, volume: f32) -> Result<(), String> {
-    let mut audio = state.audio.lock()
-        .map_err(|e| format!("Audio lock poisoned: {}", e))?;
-    audio.play_white_noise(volume)
+fn play_sound(state: tauri::State<AppState>, id: String, volume: f32) -> Result<(), MoodPulseError> {
+    check_volume(volume)?;
+    let sound = find_sound(&id)?;
+    state.audio(move |mixer| mixer.play_sound(sound, volume))?
+        .map_err(MoodPulseError::AudioDevice)
 }
 
+/// Plays the default track; kept for callers that predate the sound catalog
 #[tauri::command]
-fn stop_white_noise(state: tauri::State<AppState>) -> Result<(), String> {
-    let mut audio = state.audio.lock()
-        .map_err(|e| format!("Audio lock poisoned: {}", e))?;
-    audio.stop();
-    Ok(())
+fn play_white_noise(state: tauri::State<AppState>, volume: f32) -> Result<(), MoodPulseError> {
+    play_sound(state, sounds::DEFAULT_SOUND.to_string(), volume)
 }
 
 #[tauri::command]
-fn set_white_noise_volume(state: tauri::State<AppState>, volume: f32) -> Result<(), String> {
-    let audio = state.audio.lock()
-        .map_err(|e| format!("Audio lock poisoned: {}", e))?;
-    audio.set_volume(volume);
-    Ok(())
+fn stop_white_noise(state: tauri::State<AppState>) -> Result<(), MoodPulseError> {
+    state.audio(|mixer| mixer.stop())
+}
+
+#[tauri::command]
+fn set_white_noise_volume(state: tauri::State<AppState>, volume: f32) -> Result<(), MoodPulseError> {
+    check_volume(volume)?;
+    state.audio(move |mixer| mixer.set_volume(volume))
+}
+
+fn find_sound(id: &str) -> Result<&'static sounds::Sound, MoodPulseError> {
+    sounds::find(id).ok_or_else(|| MoodPulseError::Validation(format!("Unknown sound '{}'", id)))
+}
+
+fn not_playing(id: &str) -> MoodPulseError {
+    MoodPulseError::Validation(format!("Sound '{}' is not in the mix", id))
+}
+
+#[tauri::command]
+fn get_mixer_state(state: tauri::State<AppState>) -> Result<MixerState, MoodPulseError> {
+    state.audio(|mixer| mixer.state())
+}
+
+#[tauri::command]
+fn add_sound_layer(state: tauri::State<AppState>, id: String, volume: f32) -> Result<MixerState, MoodPulseError> {
+    check_volume(volume)?;
+    let sound = find_sound(&id)?;
+    state.audio(move |mixer| mixer.add_layer(sound, volume).map(|_| mixer.state()))?
+        .map_err(MoodPulseError::AudioDevice)
+}
+
+#[tauri::command]
+fn remove_sound_layer(state: tauri::State<AppState>, id: String) -> Result<MixerState, MoodPulseError> {
+    let sound_id = id.clone();
+    state.audio(move |mixer| mixer.remove_layer(&sound_id).then(|| mixer.state()))?
+        .ok_or_else(|| not_playing(&id))
+}
+
+#[tauri::command]
+fn set_sound_layer_volume(state: tauri::State<AppState>, id: String, volume: f32) -> Result<MixerState, MoodPulseError> {
+    check_volume(volume)?;
+    let sound_id = id.clone();
+    state.audio(move |mixer| mixer.set_layer_volume(&sound_id, volume).then(|| mixer.state()))?
+        .ok_or_else(|| not_playing(&id))
+}
+
+#[tauri::command]
+fn set_sound_layer_muted(state: tauri::State<AppState>, id: String, muted: bool) -> Result<MixerState, MoodPulseError> {
+    let sound_id = id.clone();
+    state.audio(move |mixer| mixer.set_layer_muted(&sound_id, muted).then(|| mixer.state()))?
+        .ok_or_else(|| not_playing(&id))
+}
+
+#[tauri::command]
+fn set_master_volume(state: tauri::State<AppState>, volume: f32) -> Result<MixerState, MoodPulseError> {
+    check_volume(volume)?;
+    state.audio(move |mixer| {
+        mixer.set_volume(volume);
+        mixer.state()
+    })
+}
+
+/// Save the layers currently playing under `name`, replacing any mix of that name
+#[tauri::command]
+fn save_sound_mix(state: tauri::State<AppState>, name: String) -> Result<SoundMix, MoodPulseError> {
+    let current = state.audio(|mixer| mixer.state())?;
+    let mix = SoundMix::new(&name, current.master_volume, current.layers, Local::now());
+    mix.validate().map_err(MoodPulseError::Validation)?;
+    state.db()?.save_sound_mix(&mix)?;
+    Ok(mix)
+}
+
+#[tauri::command]
+fn load_sound_mix(state: tauri::State<AppState>, name: String) -> Result<MixerState, MoodPulseError> {
+    let mix = state.db()?.get_sound_mix(name.trim())?
+        .ok_or_else(|| MoodPulseError::Validation(format!("No saved mix named '{}'", name)))?;
+    state.audio(move |mixer| mixer.load_mix(mix.master_volume, &mix.layers).map(|_| mixer.state()))?
+        .map_err(MoodPulseError::AudioDevice)
+}
+
+#[tauri::command]
+fn list_sound_mixes(state: tauri::State<AppState>) -> Result<Vec<SoundMix>, MoodPulseError> {
+    Ok(state.db()?.get_sound_mixes()?)
+}
+
+#[tauri::command]
+fn delete_sound_mix(state: tauri::State<AppState>, name: String) -> Result<bool, MoodPulseError> {
+    Ok(state.db()?.delete_sound_mix(name.trim())?)
+}
+
+/// Superseded by get_playback_status, which also reports the timer
+#[tauri::command]
+fn is_white_noise_playing(state: tauri::State<AppState>) -> Result<bool, MoodPulseError> {
+    state.audio(|mixer| mixer.is_playing())
+}
+
+#[tauri::command]
+fn get_playback_status(state: tauri::State<AppState>) -> Result<PlaybackStatus, MoodPulseError> {
+    state.audio(|mixer| mixer.status(Local::now()))
+}
+
+/// Stop playback after `minutes` or at the next local `until` ("HH:MM");
+/// exactly one of the two must be given. Fades out unless `fade_out` is false.
+#[tauri::command]
+fn set_sound_timer(
+    state: tauri::State<AppState>,
+    minutes: Option<u32>,
+    until: Option<String>,
+    fade_out: Option<bool>,
+) -> Result<PlaybackStatus, MoodPulseError> {
+    let now = Local::now();
+    let fade_out = fade_out.unwrap_or(true);
+    let timer = match (minutes, until) {
+        (Some(minutes), None) => SleepTimer::after(minutes, fade_out, now),
+        (None, Some(until)) => SleepTimer::at(&until, fade_out, now),
+        _ => Err("Give either minutes or until".to_string()),
+    }
+    .map_err(MoodPulseError::Validation)?;
+
+    state.audio(move |mixer| mixer.set_timer(timer).map(|_| mixer.status(now)))?
+        .map_err(MoodPulseError::AudioDevice)
+}
+
+#[tauri::command]
+fn cancel_sound_timer(state: tauri::State<AppState>) -> Result<PlaybackStatus, MoodPulseError> {
+    state.audio(|mixer| {
+        mixer.cancel_timer();
+        mixer.status(Local::now())
+    })
+}
+
+/// One tick of the sound timer scheduler; skipped without a round trip to
+/// the audio thread while no timer is set
+fn tick_sound_timer(app: &tauri::AppHandle) {
+    let state = app.state::<AppState>();
+    if !state.audio.has_timer() {
+        return;
+    }
+    let now = Local::now();
+    let finished = state.audio(move |mixer| mixer.expire_timer(now).then(|| mixer.status(now)));
+    if let Ok(Some(status)) = finished {
+        events::publish_timer_finished(app, status);
+    }
 }
 
 #[tauri::command]
-fn is_white_noise_playing(state: tauri::State<AppState>) -> Result<bool, String> {
-    let audio = state.audio.lock()
-        .map_err(|e| format!("Audio lock poisoned: {}", e))?;
-    Ok(audio.is_playing())
+fn get_fade_settings(state: tauri::State<AppState>) -> Result<FadeSettings, MoodPulseError> {
+    state.audio(|mixer| mixer.fade_settings())
+}
+
+#[tauri::command]
+fn set_fade_settings(
+    state: tauri::State<AppState>,
+    fade_in_ms: u64,
+    fade_out_ms: u64,
+    crossfade_ms: u64,
+) -> Result<FadeSettings, MoodPulseError> {
+    let fades = FadeSettings { fade_in_ms, fade_out_ms, crossfade_ms };
+    fades.validate().map_err(MoodPulseError::Validation)?;
+    state.audio(move |mixer| mixer.set_fade_settings(fades))?;
+    Ok(fades)
 }
 
 #[tauri::command]
-fn get_emotion_history(state: tauri::State<AppState>, limit: i64) -> Result<String, String> {
-    let db = state.db.lock()
-        .map_err(|e| format!("Database lock poisoned: {}", e))?;
-    let records = db.get_recent_records(limit)
-        .map_err(|e| format!("Database error: {}", e))?;
-    serde_json::to_string(&records)
-        .map_err(|e| format!("Serialization error: {}", e))
+fn get_emotion_history(state: tauri::State<AppState>, limit: i64) -> Result<Vec<EmotionRecord>, MoodPulseError> {
+    Ok(state.db()?.get_recent_records(limit)?)
 }
 
 #[tauri::command]
-fn get_emotion_stats(state: tauri::State<AppState>, date: String) -> Result<String, String> {
-    let db = state.db.lock()
-        .map_err(|e| format!("Database lock poisoned: {}", e))?;
-    let stats = db.get_emotion_stats(&date)
-        .map_err(|e| format!("Database error: {}", e))?;
-    serde_json::to_string(&stats)
-        .map_err(|e| format!("Serialization error: {}", e))
+fn get_emotion_stats(state: tauri::State<AppState>, date: String) -> Result<Vec<(String, i64)>, MoodPulseError> {
+    Ok(state.db()?.get_emotion_stats(&date)?)
 }
 
 #[tauri::command]
@@ -157,55 +623,200 @@ fn get_emotion_by_date_range(
     state: tauri::State<AppState>,
     start_date: String,
     end_date: String
-) -> Result<String, String> {
-    let db = state.db.lock()
-        .map_err(|e| format!("Database lock poisoned: {}", e))?;
-    let records = db.get_records_by_date_range(&start_date, &end_date)
-        .map_err(|e| format!("Database error: {}", e))?;
-    serde_json::to_string(&records)
-        .map_err(|e| format!("Serialization error: {}", e))
+) -> Result<Vec<EmotionRecord>, MoodPulseError> {
+    Ok(state.db()?.get_records_by_date_range(&start_date, &end_date)?)
 }
 
 #[tauri::command]
-fn analyze_today_emotion(state: tauri::State<AppState>) -> Result<String, String> {
-    let db = state.db.lock()
-        .map_err(|e| format!("Database lock poisoned: {}", e))?;
+fn analyze_today_emotion(state: tauri::State<AppState>) -> Result<EmotionAnalysis, MoodPulseError> {
+    let (records, checkins) = state.today()?;
+    let blend = CheckinBlend { checkins: &checkins, weight: state.checkin_weight() };
+    Ok(analyze_today_emotions(&records, &blend))
+}
 
-    let records = db.get_today_records()
-        .map_err(|e| format!("Database error: {}", e))?;
+#[tauri::command]
+fn get_emotion_timeline_data(state: tauri::State<AppState>) -> Result<Vec<TimelinePoint>, MoodPulseError> {
+    let (records, checkins) = state.today()?;
+    let blend = CheckinBlend { checkins: &checkins, weight: state.checkin_weight() };
+    Ok(get_emotion_timeline(&records, &blend))
+}
 
-    let analysis = analyze_today_emotions(records);
+#[tauri::command]
+fn analyze_focus_time_today(state: tauri::State<AppState>) -> Result<FocusAnalysis, MoodPulseError> {
+    let records = state.db()?.get_today_records()?;
+    Ok(analyze_focus_time(records))
+}
 
-    serde_json::to_string(&analysis)
-        .map_err(|e| format!("Serialization error: {}", e))
+/// Analyses for past days. Days still within the retention window are
+/// computed from raw records; days retention has compacted fall back to
+/// their daily summaries, and to their rollups for the timeline.
+#[tauri::command]
+fn analyze_emotion_by_date(state: tauri::State<AppState>, date: String) -> Result<EmotionAnalysis, MoodPulseError> {
+    analyze_emotion_by_date_range(state, date.clone(), date)
 }
 
 #[tauri::command]
-fn get_emotion_timeline_data(state: tauri::State<AppState>) -> Result<String, String> {
-    let db = state.db.lock()
-        .map_err(|e| format!("Database lock poisoned: {}", e))?;
+fn analyze_emotion_by_date_range(
+    state: tauri::State<AppState>,
+    start_date: String,
+    end_date: String
+) -> Result<EmotionAnalysis, MoodPulseError> {
+    let (start, end) = parse_date_range(&start_date, &end_date)?;
+    let (records, checkins) = state.between(start, end)?;
+    let (summaries, rollups) = state.compacted_between(start, end)?;
+    let blend = CheckinBlend { checkins: &checkins, weight: state.checkin_weight() };
+    Ok(analyze_emotions_in_range(&records, &summaries, &rollups, &blend))
+}
 
-    let records = db.get_today_records()
-        .map_err(|e| format!("Database error: {}", e))?;
+#[tauri::command]
+fn get_emotion_timeline_by_date(state: tauri::State<AppState>, date: String) -> Result<Vec<TimelinePoint>, MoodPulseError> {
+    get_emotion_timeline_by_date_range(state, date.clone(), date)
+}
 
-    let timeline = get_emotion_timeline(records);
+/// Across several days each point is labelled "YYYY-MM-DD H:MM" so the days
+/// stay apart
+#[tauri::command]
+fn get_emotion_timeline_by_date_range(
+    state: tauri::State<AppState>,
+    start_date: String,
+    end_date: String
+) -> Result<Vec<TimelinePoint>, MoodPulseError> {
+    let (start, end) = parse_date_range(&start_date, &end_date)?;
+    let (records, checkins) = state.between(start, end)?;
+    let (_, rollups) = state.compacted_between(start, end)?;
+    let blend = CheckinBlend { checkins: &checkins, weight: state.checkin_weight() };
+    Ok(get_emotion_timeline_in_range(&records, &rollups, &blend, start != end))
+}
+
+#[tauri::command]
+fn analyze_focus_time_by_date(state: tauri::State<AppState>, date: String) -> Result<FocusAnalysis, MoodPulseError> {
+    analyze_focus_time_by_date_range(state, date.clone(), date)
+}
 
-    serde_json::to_string(&timeline)
-        .map_err(|e| format!("Serialization error: {}", e))
+#[tauri::command]
+fn analyze_focus_time_by_date_range(
+    state: tauri::State<AppState>,
+    start_date: String,
+    end_date: String
+) -> Result<FocusAnalysis, MoodPulseError> {
+    let (start, end) = parse_date_range(&start_date, &end_date)?;
+    let (records, _) = state.between(start, end)?;
+    let (summaries, rollups) = state.compacted_between(start, end)?;
+    Ok(analyze_focus_time_in_range(records, &summaries, &rollups))
 }
 
+/// Write detected records to a user-chosen file. Dates are datetimes as in
+/// load_emotion_data; `fields` picks and orders the columns (all when omitted).
 #[tauri::command]
-fn analyze_focus_time_today(state: tauri::State<AppState>) -> Result<String, String> {
-    let db = state.db.lock()
-        .map_err(|e| format!("Database lock poisoned: {}", e))?;
+fn export_emotion_records(
+    state: tauri::State<AppState>,
+    path: String,
+    format: ExportFormat,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    fields: Option<Vec<String>>
+) -> Result<ExportSummary, MoodPulseError> {
+    let target = PathBuf::from(&path);
+    if !target.is_absolute() {
+        return Err(MoodPulseError::Validation(format!("Export path must be absolute, got '{}'", path)));
+    }
+    let fields = export::resolve_fields(fields)?;
+    let (start, end) = parse_datetime_range(
+        start_date.as_deref().unwrap_or("0000-01-01 00:00:00"),
+        end_date.as_deref().unwrap_or("9999-12-31 23:59:59"),
+    )?;
+
+    // Locks the database one batch at a time, so detections carry on during a long export
+    let rows = export::export_records(&target, format, &fields, |after| {
+        Ok::<_, MoodPulseError>(state.db()?.get_records_page(&start, &end, after, export::BATCH_SIZE)?)
+    })?;
+    Ok(ExportSummary { path, rows })
+}
 
-    let records = db.get_today_records()
-        .map_err(|e| format!("Database error: {}", e))?;
+/// Longest range build_period_report accepts
+const MAX_REPORT_DAYS: i64 = 366;
+
+/// Weekly or monthly report. It is built from daily summaries, so it also
+/// covers days whose raw records retention has compacted.
+#[tauri::command]
+fn build_period_report(
+    state: tauri::State<AppState>,
+    start_date: String,
+    end_date: String
+) -> Result<PeriodReport, MoodPulseError> {
+    let (start, end) = parse_date_range(&start_date, &end_date)?;
+    if (end - start).num_days() >= MAX_REPORT_DAYS {
+        return Err(MoodPulseError::Validation(format!("Reports cover at most {} days", MAX_REPORT_DAYS)));
+    }
+    let from = emotion_analysis::comparison_start(start, end).format("%Y-%m-%d").to_string();
+    let summaries = state.db()?.get_daily_summaries(&from, &end.format("%Y-%m-%d").to_string())?;
+    Ok(emotion_analysis::build_period_report(start, end, &summaries))
+}
 
-    let focus_analysis = analyze_focus_time(records);
+fn create_emotion_backend(kind: BackendKind, app_data_dir: &Path) -> Box<dyn EmotionBackend> {
+    match kind {
+        BackendKind::Python => {
+            let python_path = std::env::var("MOODPULSE_PYTHON_PATH")
+                .or_else(|_| std::env::var("PYTHON_PATH"))
+                .unwrap_or_else(|_| "python".to_string());
+            let script_path = std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(|p| p.to_path_buf()))
+                .map(|mut p| {
+                    if cfg!(debug_assertions) {
+                        p.pop();
+                        p.pop();
+                    }
+                    p.push("emotion_service.py");
+                    p
+                })
+                .expect("Failed to determine script path");
+
+            let emotion_service = EmotionService::new(
+                python_path,
+                script_path.to_string_lossy().to_string()
+            );
+            if let Err(e) = emotion_service.set_log_dir(app_data_dir.join("logs")) {
+                eprintln!("Failed to set up emotion service log: {}", e);
+            }
+            if let Some(ms) = std::env::var("MOODPULSE_DETECT_TIMEOUT_MS").ok().and_then(|v| v.parse().ok()) {
+                emotion_service.set_detect_timeout(Duration::from_millis(ms));
+            }
+            if let Ok(path) = std::env::var("MOODPULSE_RECORD_FILE") {
+                if let Err(e) = emotion_service.set_recording(Some(PathBuf::from(path))) {
+                    eprintln!("{}", e);
+                }
+            }
 
-    serde_json::to_string(&focus_analysis)
-        .map_err(|e| format!("Serialization error: {}", e))
+            Box::new(emotion_service)
+        }
+        BackendKind::Mock => {
+            // A scenario file wins over a bare seed
+            let scenario = std::env::var("MOODPULSE_MOCK_SCENARIO").ok()
+                .and_then(|path| Scenario::from_file(Path::new(&path))
+                    .map_err(|e| eprintln!("{}, using seeded scenario", e))
+                    .ok());
+            let seed = std::env::var("MOODPULSE_MOCK_SEED").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+
+            Box::new(match scenario {
+                Some(scenario) => MockBackend::new(scenario),
+                None => MockBackend::from_seed(seed),
+            })
+        }
+        BackendKind::Replay => {
+            let path = std::env::var("MOODPULSE_REPLAY_FILE")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| app_data_dir.join("recording.jsonl"));
+            let speed = std::env::var("MOODPULSE_REPLAY_SPEED").ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1.0);
+            let repeat = std::env::var("MOODPULSE_REPLAY_LOOP").map(|v| v == "1").unwrap_or(false);
+
+            Box::new(ReplayBackend::new(path, speed, repeat))
+        }
+    }
 }
 
 fn main() {
@@ -215,39 +826,32 @@ fn main() {
     std::fs::create_dir_all(&app_data_dir).expect("Failed to create app data directory");
     let db_path = app_data_dir.join("emotions.db");
     let db = Database::new(db_path).expect("Failed to open database");
-    db.init().expect("Failed to initialize database");
-
-    // Initialize emotion service
-    let python_path = std::env::var("MOODPULSE_PYTHON_PATH")
-        .or_else(|_| std::env::var("PYTHON_PATH"))
-        .unwrap_or_else(|_| "python".to_string());
-    let script_path = std::env::current_exe()
-        .ok()
-        .and_then(|exe| exe.parent().map(|p| p.to_path_buf()))
-        .map(|mut p| {
-            if cfg!(debug_assertions) {
-                p.pop();
-                p.pop();
-            }
-            p.push("emotion_service.py");
-            p
-        })
-        .expect("Failed to determine script path");
+    // Refuses to touch a database written by a newer build
+    db.init().unwrap_or_else(|e| panic!("Failed to initialize database: {}", e));
 
-    let emotion_service = EmotionService::new(
-        python_path,
-        script_path.to_string_lossy().to_string()
-    );
+    // Initialize emotion backend
+    let emotion_backend = create_emotion_backend(BackendKind::from_env(), &app_data_dir);
 
-    // Start the emotion service
-    if let Err(e) = emotion_service.start() {
-        eprintln!("Failed to start emotion service: {}", e);
+    // Start the emotion backend
+    if let Err(e) = emotion_backend.start() {
+        eprintln!("Failed to start {} emotion backend: {}", emotion_backend.name(), e);
     }
 
     let app_state = AppState {
         db: Mutex::new(db),
-        audio: Mutex::new(AudioPlayer::new()),
-        emotion_service,
+        audio: AudioPlayer::new(),
+        emotion_backend,
+        scheduler: IntervalScheduler::new(scheduler::DEFAULT_INTERVAL),
+        break_due: AtomicBool::new(false),
+        checkin_weight: Mutex::new(
+            std::env::var("MOODPULSE_CHECKIN_WEIGHT").ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|w| (0.0..=1.0).contains(w))
+                .unwrap_or(emotion_analysis::DEFAULT_CHECKIN_WEIGHT)
+        ),
+        retention: Mutex::new(RetentionPolicy::from_env()),
+        retention_scheduler: IntervalScheduler::new(retention::RETENTION_INTERVAL),
+        sound_timer_scheduler: IntervalScheduler::new(sleep_timer::TIMER_TICK),
     };
 
     // System tray menu
@@ -266,21 +870,51 @@ fn main() {
     tauri::Builder::default()
         .manage(app_state)
         .system_tray(system_tray)
+        .setup(|app| {
+            // Bundled builds and `tauri dev` both copy the sounds next to the binary
+            let library = SoundLibrary::locate(app.path_resolver().resolve_resource(sounds::RESOURCE_DIR));
+            if library.list().iter().any(|sound| !sound.available) {
+                eprintln!("Some sounds are missing from {}", library.dir().display());
+            }
+            app.state::<AppState>().audio(move |mixer| mixer.set_library(library))?;
+
+            // Detection runs in the core so it keeps going while webviews are hidden
+            let handle = app.handle();
+            app.state::<AppState>().scheduler.start(move || scheduled_detection(&handle))?;
+
+            let handle = app.handle();
+            app.state::<AppState>().retention_scheduler.start(move || {
+                if let Err(e) = handle.state::<AppState>().apply_retention() {
+                    eprintln!("Retention failed: {}", e);
+                }
+            })?;
+
+            let handle = app.handle();
+            app.state::<AppState>().sound_timer_scheduler.start(move || tick_sound_timer(&handle))?;
+            Ok(())
+        })
         .on_window_event(|event| {
+            // Only the main window owns the app lifetime; the report and picker windows come and go
+            if event.window().label() != "main" {
+                return;
+            }
             if let tauri::WindowEvent::CloseRequested { api: _, .. } = event.event() {
                 let app_handle = event.window().app_handle();
                 let state = app_handle.state::<AppState>();
 
+                // Stop scheduled detection before the backend it calls
+                state.scheduler.stop();
+                state.retention_scheduler.stop();
+                state.sound_timer_scheduler.stop();
+
                 // Stop emotion service and wait for it to complete
                 println!("Closing application, stopping emotion service...");
-                if let Err(e) = state.emotion_service.stop() {
+                if let Err(e) = state.emotion_backend.stop() {
                     eprintln!("Error stopping emotion service: {}", e);
                 }
 
                 // Stop audio
-                if let Ok(mut audio) = state.audio.lock() {
-                    audio.stop();
-                };
+                let _ = state.audio(|mixer| mixer.stop_now());
 
                 // Allow window to close normally instead of forcing exit
                 // This gives time for cleanup
@@ -306,13 +940,14 @@ fn main() {
                     "quit" => {
                         let state = app.state::<AppState>();
                         println!("Quit requested from tray, stopping emotion service...");
-                        if let Err(e) = state.emotion_service.stop() {
+                        state.scheduler.stop();
+                        state.retention_scheduler.stop();
+                        state.sound_timer_scheduler.stop();
+                        if let Err(e) = state.emotion_backend.stop() {
                             eprintln!("Error stopping emotion service: {}", e);
                         }
                         // Stop audio
-                        if let Ok(mut audio) = state.audio.lock() {
-                            audio.stop();
-                        }
+                        let _ = state.audio(|mixer| mixer.stop_now());
                         // Exit the app
                         app.exit(0);
                     }
@@ -325,16 +960,56 @@ fn main() {
             save_emotion_data,
             load_emotion_data,
             detect_emotion,
+            record_mood_checkin,
+            get_mood_checkins,
+            get_checkin_weight,
+            set_checkin_weight,
+            get_retention_policy,
+            set_retention_policy,
+            get_emotion_rollups,
+            get_daily_summaries,
+            pause_detection,
+            resume_detection,
+            set_detection_interval,
+            get_detection_schedule,
+            get_emotion_service_status,
+            get_emotion_service_logs,
+            set_emotion_recording,
+            list_sounds,
+            play_sound,
             play_white_noise,
             stop_white_noise,
             set_white_noise_volume,
             is_white_noise_playing,
+            get_playback_status,
+            set_sound_timer,
+            cancel_sound_timer,
+            get_mixer_state,
+            add_sound_layer,
+            remove_sound_layer,
+            set_sound_layer_volume,
+            set_sound_layer_muted,
+            set_master_volume,
+            save_sound_mix,
+            load_sound_mix,
+            list_sound_mixes,
+            delete_sound_mix,
+            get_fade_settings,
+            set_fade_settings,
             get_emotion_history,
             get_emotion_stats,
             get_emotion_by_date_range,
             analyze_today_emotion,
             get_emotion_timeline_data,
-            analyze_focus_time_today
+            analyze_focus_time_today,
+            analyze_emotion_by_date,
+            analyze_emotion_by_date_range,
+            get_emotion_timeline_by_date,
+            get_emotion_timeline_by_date_range,
+            analyze_focus_time_by_date,
+            analyze_focus_time_by_date_range,
+            build_period_report,
+            export_emotion_records
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
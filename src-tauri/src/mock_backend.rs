@@ -0,0 +1,364 @@
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::emotion_backend::{BackendHealth, EmotionBackend};
+use crate::emotion_service::{DetectError, ServiceState, ServiceStatus, StartError};
+
+// Same break reminder threshold as emotion_service.py
+const BREAK_ALERT_MINUTES: f64 = 45.0;
+const EMOTIONS: [&str; 4] = ["happy", "calm", "worried", "tired"];
+
+/// One stretch of scripted behaviour
+#[derive(Debug, Clone, Deserialize)]
+pub struct Segment {
+    pub duration_seconds: u64,
+    /// Fixed emotion for the whole segment
+    #[serde(default)]
+    pub emotion: Option<String>,
+    /// Weighted choice per detection, e.g. {"calm": 0.7, "worried": 0.3}
+    #[serde(default)]
+    pub emotions: BTreeMap<String, f64>,
+    #[serde(default = "default_confidence")]
+    pub confidence: f64,
+    /// User left the desk
+    #[serde(default)]
+    pub away: bool,
+    /// User is present but the camera lost the face
+    #[serde(default)]
+    pub no_face: bool,
+}
+
+fn default_confidence() -> f64 {
+    0.8
+}
+
+fn default_step_seconds() -> u64 {
+    5
+}
+
+/// A scripted session: segments played back in order on a virtual clock
+/// that advances `step_seconds` per detection
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default = "default_step_seconds")]
+    pub step_seconds: u64,
+    #[serde(default)]
+    pub repeat: bool,
+    pub segments: Vec<Segment>,
+}
+
+impl Scenario {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read scenario {}: {}", path.display(), e))?;
+        let scenario: Scenario = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid scenario {}: {}", path.display(), e))?;
+        if scenario.segments.is_empty() || scenario.step_seconds == 0 {
+            return Err(format!("Scenario {} has no segments or a zero step", path.display()));
+        }
+        Ok(scenario)
+    }
+
+    /// A working day of random segments derived entirely from `seed`
+    pub fn from_seed(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut segments = Vec::new();
+
+        for _ in 0..16 {
+            let duration_seconds = 5 * 60 + rng.below(25) * 60;
+            let roll = rng.next_f64();
+            let segment = if roll < 0.1 {
+                Segment::marker(duration_seconds, true, false)
+            } else if roll < 0.15 {
+                Segment::marker(duration_seconds / 5, false, true)
+            } else {
+                let mut emotions = BTreeMap::new();
+                let main = EMOTIONS[rng.below(EMOTIONS.len() as u64) as usize];
+                let other = EMOTIONS[rng.below(EMOTIONS.len() as u64) as usize];
+                emotions.insert(main.to_string(), 0.7);
+                *emotions.entry(other.to_string()).or_insert(0.0) += 0.3;
+                Segment {
+                    duration_seconds,
+                    emotion: None,
+                    emotions,
+                    confidence: 0.6 + rng.next_f64() * 0.35,
+                    away: false,
+                    no_face: false,
+                }
+            };
+            segments.push(segment);
+        }
+
+        Scenario {
+            seed,
+            step_seconds: default_step_seconds(),
+            repeat: true,
+            segments,
+        }
+    }
+
+    fn total_seconds(&self) -> u64 {
+        self.segments.iter().map(|s| s.duration_seconds).sum()
+    }
+
+    /// The segment active `elapsed` seconds into the scenario
+    fn segment_at(&self, elapsed: u64) -> Option<&Segment> {
+        let total = self.total_seconds();
+        if total == 0 {
+            return None;
+        }
+        let mut offset = if self.repeat { elapsed % total } else { elapsed };
+        for segment in &self.segments {
+            if offset < segment.duration_seconds {
+                return Some(segment);
+            }
+            offset -= segment.duration_seconds;
+        }
+        None
+    }
+}
+
+impl Segment {
+    fn marker(duration_seconds: u64, away: bool, no_face: bool) -> Self {
+        Segment {
+            duration_seconds,
+            emotion: None,
+            emotions: BTreeMap::new(),
+            confidence: default_confidence(),
+            away,
+            no_face,
+        }
+    }
+}
+
+/// xorshift64*, small and stable across platforms so seeds replay exactly
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Zero is a fixed point of xorshift
+        Rng(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+struct MockState {
+    running: bool,
+    elapsed: u64,
+    work_start: u64,
+    rng: Rng,
+}
+
+/// Deterministic backend that plays a `Scenario` instead of using a camera
+pub struct MockBackend {
+    scenario: Scenario,
+    state: Mutex<MockState>,
+}
+
+impl MockBackend {
+    pub fn new(scenario: Scenario) -> Self {
+        let rng = Rng::new(scenario.seed);
+        MockBackend {
+            scenario,
+            state: Mutex::new(MockState {
+                running: false,
+                elapsed: 0,
+                work_start: 0,
+                rng,
+            }),
+        }
+    }
+
+    pub fn from_seed(seed: u64) -> Self {
+        MockBackend::new(Scenario::from_seed(seed))
+    }
+
+    /// Produce the next detection, advancing the virtual clock by one step
+    fn next_detection(&self, state: &mut MockState) -> serde_json::Value {
+        let elapsed = state.elapsed;
+        state.elapsed += self.scenario.step_seconds;
+
+        let segment = match self.scenario.segment_at(elapsed) {
+            Some(segment) => segment,
+            // Scenario finished: nobody in front of the camera
+            None => return detection_json(false, true, 0.0, None),
+        };
+
+        if segment.away {
+            state.work_start = elapsed;
+            return detection_json(false, true, 0.0, None);
+        }
+
+        let work_minutes = (elapsed - state.work_start) as f64 / 60.0;
+        if segment.no_face {
+            return detection_json(false, false, work_minutes, None);
+        }
+
+        let emotion = match &segment.emotion {
+            Some(emotion) => emotion.clone(),
+            None => pick_weighted(&segment.emotions, &mut state.rng),
+        };
+        let jitter = (state.rng.next_f64() - 0.5) * 0.1;
+        let confidence = (segment.confidence + jitter).clamp(0.0, 1.0);
+
+        detection_json(true, false, work_minutes, Some((emotion, confidence)))
+    }
+}
+
+fn pick_weighted(weights: &BTreeMap<String, f64>, rng: &mut Rng) -> String {
+    let total: f64 = weights.values().filter(|w| **w > 0.0).sum();
+    if total <= 0.0 {
+        return "calm".to_string();
+    }
+    let mut roll = rng.next_f64() * total;
+    for (emotion, weight) in weights.iter().filter(|(_, w)| **w > 0.0) {
+        if roll < *weight {
+            return emotion.clone();
+        }
+        roll -= weight;
+    }
+    weights.keys().next_back().cloned().unwrap_or_else(|| "calm".to_string())
+}
+
+/// Same shape as emotion_service.py's detect result; DeepFace confidence
+/// is a percentage there, so it is here too
+fn detection_json(
+    has_face: bool,
+    is_away: bool,
+    work_minutes: f64,
+    emotion: Option<(String, f64)>,
+) -> serde_json::Value {
+    let emotions = match emotion {
+        Some((emotion, confidence)) => vec![
+            json!({ "emotion": emotion, "confidence": confidence, "source": "fer" }),
+            json!({ "emotion": emotion, "confidence": confidence * 100.0, "source": "deepface" }),
+        ],
+        None => Vec::new(),
+    };
+
+    json!({
+        "has_face": has_face,
+        "is_away": is_away,
+        "work_minutes": work_minutes,
+        "need_break_alert": !is_away && work_minutes >= BREAK_ALERT_MINUTES,
+        "emotions": emotions,
+    })
+}
+
+impl EmotionBackend for MockBackend {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn start(&self) -> Result<(), StartError> {
+        if let Ok(mut state) = self.state.lock() {
+            state.running = true;
+        }
+        Ok(())
+    }
+
+    fn detect(&self, _timeout: Option<Duration>) -> Result<String, DetectError> {
+        let mut state = self.state.lock()
+            .map_err(|e| DetectError::Unavailable(format!("Mock state lock poisoned: {}", e)))?;
+        if !state.running {
+            return Err(DetectError::Unavailable("Mock backend not started".to_string()));
+        }
+        Ok(self.next_detection(&mut state).to_string())
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        if let Ok(mut state) = self.state.lock() {
+            state.running = false;
+        }
+        Ok(())
+    }
+
+    fn health(&self) -> BackendHealth {
+        let running = self.state.lock().map(|s| s.running).unwrap_or(false);
+        BackendHealth {
+            backend: self.name().to_string(),
+            status: ServiceStatus {
+                state: if running { ServiceState::Ready } else { ServiceState::Stopped },
+                pid: None,
+                restart_attempts: 0,
+                last_error: None,
+                service_info: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detections(backend: &MockBackend, n: usize) -> Vec<serde_json::Value> {
+        backend.start().unwrap();
+        (0..n)
+            .map(|_| serde_json::from_str(&backend.detect(None).unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let a = detections(&MockBackend::from_seed(7), 500);
+        let b = detections(&MockBackend::from_seed(7), 500);
+        let c = detections(&MockBackend::from_seed(8), 500);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_scenario_segments() {
+        let scenario: Scenario = serde_json::from_str(r#"{
+            "step_seconds": 60,
+            "segments": [
+                {"duration_seconds": 120, "emotion": "happy", "confidence": 0.9},
+                {"duration_seconds": 60, "away": true},
+                {"duration_seconds": 60, "no_face": true},
+                {"duration_seconds": 60, "emotion": "worried"}
+            ]
+        }"#).unwrap();
+        let results = detections(&MockBackend::new(scenario), 6);
+
+        assert_eq!(results[0]["emotions"][0]["emotion"], "happy");
+        assert_eq!(results[1]["work_minutes"], 1.0);
+        assert_eq!(results[2]["is_away"], true);
+        assert_eq!(results[3]["has_face"], false);
+        assert_eq!(results[3]["is_away"], false);
+        assert_eq!(results[4]["emotions"][0]["emotion"], "worried");
+        // Work time restarted after the away period
+        assert_eq!(results[4]["work_minutes"], 2.0);
+        // Past the end of a non-repeating scenario
+        assert_eq!(results[5]["is_away"], true);
+    }
+
+    #[test]
+    fn test_detect_requires_start() {
+        let backend = MockBackend::from_seed(1);
+        assert!(matches!(backend.detect(None), Err(DetectError::Unavailable(_))));
+        assert_eq!(backend.health().status.state, ServiceState::Stopped);
+    }
+}
@@ -0,0 +1,110 @@
+// End-to-end pipeline tests: mock backend -> detection parsing -> database -> analysis
+// Run: cargo test --package moodpulse --bin moodpulse pipeline_tests
+
+#[cfg(test)]
+mod tests {
+    use crate::db::Database;
+    use crate::detection::{save_detection, DetectionResult};
+    use crate::emotion_analysis::{analyze_focus_time, analyze_today_emotions, CheckinBlend};
+    use crate::emotion_backend::EmotionBackend;
+    use crate::mock_backend::{MockBackend, Scenario};
+    use chrono::{DateTime, Duration, Local, TimeZone};
+    use std::path::PathBuf;
+
+    fn memory_db() -> Database {
+        let db = Database::new(PathBuf::from(":memory:")).expect("Failed to open database");
+        db.init().expect("Failed to initialize database");
+        db
+    }
+
+    /// 09:00 today, so records land in get_today_records
+    fn morning() -> DateTime<Local> {
+        let today = Local::now().date_naive().and_hms_opt(9, 0, 0).unwrap();
+        Local.from_local_datetime(&today).unwrap()
+    }
+
+    /// Drive `detections` calls through the same save path as detect_emotion
+    fn run(backend: &dyn EmotionBackend, db: &Database, detections: usize, step_seconds: i64) -> usize {
+        backend.start().expect("Failed to start backend");
+        let start = morning();
+        let mut saved = 0;
+        for i in 0..detections {
+            let result = backend.detect(None).expect("Detection failed");
+            let now = start + Duration::seconds(i as i64 * step_seconds);
+            if save_detection(db, &result, now).expect("Failed to save").is_some() {
+                saved += 1;
+            }
+        }
+        backend.stop().unwrap();
+        saved
+    }
+
+    #[test]
+    fn test_scenario_pipeline() {
+        let scenario: Scenario = serde_json::from_str(r#"{
+            "seed": 3,
+            "step_seconds": 60,
+            "segments": [
+                {"duration_seconds": 2400, "emotion": "happy", "confidence": 0.9},
+                {"duration_seconds": 600, "away": true},
+                {"duration_seconds": 300, "no_face": true},
+                {"duration_seconds": 1200, "emotion": "worried", "confidence": 0.8}
+            ]
+        }"#).unwrap();
+        let db = memory_db();
+
+        let saved = run(&MockBackend::new(scenario), &db, 75, 60);
+        // Away and face-lost detections carry no emotion and are not stored
+        assert_eq!(saved, 40 + 20);
+
+        let records = db.get_today_records().expect("Failed to load records");
+        assert_eq!(records.len(), 60);
+        assert!(records.iter().all(|r| r.fer_confidence <= 1.0));
+        assert!(records.iter().all(|r| r.deepface_confidence.unwrap() <= 1.0));
+
+        let analysis = analyze_today_emotions(&records, &CheckinBlend::default());
+        assert_eq!(analysis.total_records, 60);
+        assert_eq!(analysis.valid_records, 60);
+        // Mostly happy, but ending worried pulls the peak-end score down
+        assert!(analysis.emotion_index > 3.0 && analysis.emotion_index < 9.0);
+        assert!(analysis.stress_level > 0.0);
+
+        let focus = analyze_focus_time(db.get_today_records().unwrap());
+        assert_eq!(focus.total_focus_sessions, 1);
+        assert!(focus.is_currently_focusing);
+    }
+
+    #[test]
+    fn test_seeded_pipeline_is_deterministic() {
+        let first = memory_db();
+        let second = memory_db();
+        run(&MockBackend::from_seed(42), &first, 300, 5);
+        run(&MockBackend::from_seed(42), &second, 300, 5);
+
+        let a = analyze_today_emotions(&first.get_today_records().unwrap(), &CheckinBlend::default());
+        let b = analyze_today_emotions(&second.get_today_records().unwrap(), &CheckinBlend::default());
+        assert!(a.total_records > 0);
+        assert_eq!(a.total_records, b.total_records);
+        assert_eq!(a.emotion_index, b.emotion_index);
+        assert_eq!(a.stress_level, b.stress_level);
+    }
+
+    #[test]
+    fn test_detection_result_carries_break_alert() {
+        let scenario: Scenario = serde_json::from_str(r#"{
+            "step_seconds": 600,
+            "segments": [{"duration_seconds": 6000, "emotion": "tired"}]
+        }"#).unwrap();
+        let backend = MockBackend::new(scenario);
+        backend.start().unwrap();
+
+        let results: Vec<DetectionResult> = (0..6)
+            .map(|_| DetectionResult::parse(&backend.detect(None).unwrap()).expect("Unparseable detection"))
+            .collect();
+        assert!(!results[4].need_break_alert);
+        assert!(results[5].need_break_alert);
+        assert_eq!(results[5].work_minutes, 50.0);
+        assert_eq!(results[5].emotions[0].emotion, "tired");
+        assert_eq!(results[5].emotions[1].source.as_deref(), Some("deepface"));
+    }
+}
@@ -0,0 +1,346 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::emotion_backend::{BackendHealth, EmotionBackend};
+use crate::emotion_service::{DetectError, ServiceState, ServiceStatus, StartError};
+
+/// One line of a recorded session file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub timestamp_ms: i64,
+    #[serde(default)]
+    pub datetime: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+/// Appends every raw detection response to a JSONL file
+pub struct SessionRecorder {
+    path: PathBuf,
+    file: File,
+}
+
+impl SessionRecorder {
+    pub fn create(path: PathBuf) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open recording {}: {}", path.display(), e))?;
+        Ok(SessionRecorder { path, file })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&mut self, result: Option<&Value>, error: Option<&str>, code: Option<&str>) {
+        let now = Local::now();
+        let entry = RecordedResponse {
+            timestamp_ms: now.timestamp_millis(),
+            datetime: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+            result: result.cloned(),
+            error: error.map(|e| e.to_string()),
+            code: code.map(|c| c.to_string()),
+        };
+        let written = serde_json::to_string(&entry)
+            .map_err(|e| e.to_string())
+            .and_then(|line| writeln!(self.file, "{}", line).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            eprintln!("Failed to record detection to {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+struct ReplayState {
+    lines: Option<Lines<BufReader<File>>>,
+    // Timestamp of the previous response and when we handed it out
+    previous: Option<(i64, Instant)>,
+    last_error: Option<String>,
+}
+
+/// Plays a recorded session back through `detect`, keeping the original
+/// spacing between responses divided by `speed` (0 replays without pauses)
+pub struct ReplayBackend {
+    path: PathBuf,
+    speed: f64,
+    repeat: bool,
+    state: Mutex<ReplayState>,
+}
+
+impl ReplayBackend {
+    pub fn new(path: PathBuf, speed: f64, repeat: bool) -> Self {
+        ReplayBackend {
+            path,
+            speed: speed.max(0.0),
+            repeat,
+            state: Mutex::new(ReplayState {
+                lines: None,
+                previous: None,
+                last_error: None,
+            }),
+        }
+    }
+
+    fn open(&self) -> Result<Lines<BufReader<File>>, String> {
+        File::open(&self.path)
+            .map(|file| BufReader::new(file).lines())
+            .map_err(|e| format!("Failed to open replay file {}: {}", self.path.display(), e))
+    }
+
+    /// Next parseable entry, rewinding once at the end when repeating
+    fn next_entry(&self, state: &mut ReplayState) -> Result<Option<RecordedResponse>, String> {
+        let mut rewound = false;
+        loop {
+            let lines = match state.lines.as_mut() {
+                Some(lines) => lines,
+                None => return Ok(None),
+            };
+            match lines.next() {
+                Some(Ok(line)) => {
+                    if let Ok(entry) = serde_json::from_str::<RecordedResponse>(&line) {
+                        return Ok(Some(entry));
+                    }
+                }
+                Some(Err(e)) => return Err(format!("Failed to read replay file: {}", e)),
+                None if self.repeat && !rewound => {
+                    state.lines = Some(self.open()?);
+                    state.previous = None;
+                    rewound = true;
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+impl EmotionBackend for ReplayBackend {
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+
+    fn start(&self) -> Result<(), StartError> {
+        let lines = self.open().map_err(|message| StartError::Config { message })?;
+        if let Ok(mut state) = self.state.lock() {
+            state.lines = Some(lines);
+            state.previous = None;
+            state.last_error = None;
+        }
+        println!("Replaying {} at {}x", self.path.display(), self.speed);
+        Ok(())
+    }
+
+    fn detect(&self, timeout: Option<Duration>) -> Result<String, DetectError> {
+        let mut state = self.state.lock()
+            .map_err(|e| DetectError::Unavailable(format!("Replay state lock poisoned: {}", e)))?;
+        if state.lines.is_none() {
+            return Err(DetectError::Unavailable("Replay backend not started".to_string()));
+        }
+
+        let entry = match self.next_entry(&mut state) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => return Err(DetectError::Unavailable("Replay finished".to_string())),
+            Err(message) => {
+                state.last_error = Some(message.clone());
+                return Err(DetectError::Io(message));
+            }
+        };
+
+        // Reproduce the recorded cadence, compressed by `speed`
+        let mut wait = Duration::ZERO;
+        if let Some((previous_ts, emitted_at)) = state.previous {
+            if self.speed > 0.0 {
+                let gap_ms = (entry.timestamp_ms - previous_ts).max(0) as f64 / self.speed;
+                let due = emitted_at + Duration::from_millis(gap_ms as u64);
+                wait = due.saturating_duration_since(Instant::now());
+                if let Some(timeout) = timeout {
+                    wait = wait.min(timeout);
+                }
+            }
+        }
+        state.previous = Some((entry.timestamp_ms, Instant::now() + wait));
+
+        // Sleep without the lock so stop and health are not held up by the pacing
+        if !wait.is_zero() {
+            drop(state);
+            thread::sleep(wait);
+            let state = self.state.lock()
+                .map_err(|e| DetectError::Unavailable(format!("Replay state lock poisoned: {}", e)))?;
+            if state.lines.is_none() {
+                return Err(DetectError::Unavailable("Replay backend stopped".to_string()));
+            }
+        }
+
+        match (entry.result, entry.error) {
+            (_, Some(error)) if entry.code.as_deref() == Some("camera") => Err(DetectError::Camera(error)),
+            (_, Some(error)) => Err(DetectError::Service(error)),
+            (Some(mut result), None) => {
+                // Keep the original time so the detection is stored when it happened
+                if let Some(object) = result.as_object_mut() {
+                    object.insert("recorded_at_ms".to_string(), Value::from(entry.timestamp_ms));
+                }
+                Ok(result.to_string())
+            }
+            (None, None) => Ok(Value::Null.to_string()),
+        }
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        if let Ok(mut state) = self.state.lock() {
+            state.lines = None;
+            state.previous = None;
+        }
+        Ok(())
+    }
+
+    fn health(&self) -> BackendHealth {
+        let (running, last_error) = match self.state.lock() {
+            Ok(state) => (state.lines.is_some(), state.last_error.clone()),
+            Err(_) => (false, None),
+        };
+        BackendHealth {
+            backend: self.name().to_string(),
+            status: ServiceStatus {
+                state: if running { ServiceState::Ready } else { ServiceState::Stopped },
+                pid: None,
+                restart_attempts: 0,
+                last_error,
+                service_info: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detection::DetectionResult;
+    use serde_json::json;
+
+    fn write_session(name: &str, entries: &[RecordedResponse]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("moodpulse_{}.jsonl", name));
+        let mut text = String::new();
+        for entry in entries {
+            text.push_str(&serde_json::to_string(entry).unwrap());
+            text.push('\n');
+        }
+        // A truncated line from a crash mid-write is skipped
+        text.push_str("{\"timestamp_ms\": 12\n");
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn entry(timestamp_ms: i64, emotion: &str) -> RecordedResponse {
+        RecordedResponse {
+            timestamp_ms,
+            datetime: String::new(),
+            result: Some(json!({"has_face": true, "emotions": [{"emotion": emotion, "confidence": 0.9}]})),
+            error: None,
+            code: None,
+        }
+    }
+
+    #[test]
+    fn test_replay_in_order_then_finishes() {
+        let mut failed = entry(2_000, "calm");
+        failed.result = None;
+        failed.error = Some("Cannot open camera".to_string());
+        let path = write_session("replay_order", &[entry(0, "happy"), failed, entry(4_000, "worried")]);
+
+        let backend = ReplayBackend::new(path.clone(), 0.0, false);
+        backend.start().unwrap();
+
+        let first: Value = serde_json::from_str(&backend.detect(None).unwrap()).unwrap();
+        assert_eq!(first["emotions"][0]["emotion"], "happy");
+        assert!(matches!(backend.detect(None), Err(DetectError::Service(_))));
+        let third = backend.detect(None).unwrap();
+        let detection = DetectionResult::parse(&third).unwrap();
+        assert_eq!(detection.emotions[0].emotion, "worried");
+        // Stored at the recorded time, not when it was replayed
+        assert_eq!(detection.detected_at(Local::now()).timestamp_millis(), 4_000);
+        assert!(matches!(backend.detect(None), Err(DetectError::Unavailable(_))));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_replay_time_compression() {
+        let path = write_session("replay_speed", &[entry(0, "happy"), entry(2_000, "calm")]);
+        let backend = ReplayBackend::new(path.clone(), 20.0, true);
+        backend.start().unwrap();
+
+        backend.detect(None).unwrap();
+        let started = Instant::now();
+        backend.detect(None).unwrap();
+        let waited = started.elapsed();
+        assert!(waited >= Duration::from_millis(90), "waited {:?}", waited);
+        assert!(waited < Duration::from_millis(1000), "waited {:?}", waited);
+
+        // Repeating wraps around to the first entry
+        let again: Value = serde_json::from_str(&backend.detect(None).unwrap()).unwrap();
+        assert_eq!(again["emotions"][0]["emotion"], "happy");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_stop_does_not_wait_for_pacing() {
+        let path = write_session("replay_pacing", &[entry(0, "happy"), entry(60_000, "calm")]);
+        let backend = std::sync::Arc::new(ReplayBackend::new(path.clone(), 60.0, false));
+        backend.start().unwrap();
+        backend.detect(None).unwrap();
+
+        // The second detection paces for about a second
+        let pacing = std::sync::Arc::clone(&backend);
+        let detect = thread::spawn(move || pacing.detect(None));
+        thread::sleep(Duration::from_millis(100));
+
+        let started = Instant::now();
+        assert_eq!(backend.health().status.state, ServiceState::Ready);
+        backend.stop().unwrap();
+        assert!(started.elapsed() < Duration::from_millis(500), "waited {:?}", started.elapsed());
+        assert!(matches!(detect.join().unwrap(), Err(DetectError::Unavailable(_))));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_recorder_output_replays() {
+        let path = std::env::temp_dir().join("moodpulse_recorder_roundtrip.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let mut recorder = SessionRecorder::create(path.clone()).unwrap();
+        recorder.record(Some(&json!({"has_face": false, "emotions": []})), None, None);
+        recorder.record(None, Some("DeepFace crashed"), None);
+        recorder.record(None, Some("Cannot open camera"), Some("camera"));
+        drop(recorder);
+
+        let backend = ReplayBackend::new(path.clone(), 0.0, false);
+        backend.start().unwrap();
+        assert!(backend.detect(None).unwrap().contains("has_face"));
+        match backend.detect(None) {
+            Err(DetectError::Service(message)) => assert_eq!(message, "DeepFace crashed"),
+            other => panic!("expected Service error, got {:?}", other),
+        }
+        assert!(matches!(backend.detect(None), Err(DetectError::Camera(_))));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_start_fails_for_missing_file() {
+        let backend = ReplayBackend::new(PathBuf::from("/nonexistent/session.jsonl"), 1.0, false);
+        assert!(matches!(backend.start(), Err(StartError::Config { .. })));
+    }
+}
@@ -0,0 +1,278 @@
+use chrono::{DateTime, Duration, Local, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+use crate::db::{Database, EmotionRecord, EmotionRollup};
+
+pub const DEFAULT_RAW_DAYS: u32 = 30;
+
+/// How often the retention pass runs after the one at startup
+pub const RETENTION_INTERVAL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Focused records further apart than this add no focus time, as in analyze_focus_time
+const MAX_FOCUS_GAP_SECONDS: i64 = 60;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// How long raw `emotion_records` are kept before they are compacted into
+/// half-hour rollups
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RetentionPolicy {
+    /// Days of raw records to keep, counting today; 0 keeps them forever
+    pub raw_days: u32,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy { raw_days: DEFAULT_RAW_DAYS }
+    }
+}
+
+impl RetentionPolicy {
+    pub fn from_env() -> Self {
+        std::env::var("MOODPULSE_RETENTION_DAYS").ok()
+            .and_then(|v| v.parse().ok())
+            .map(|raw_days| RetentionPolicy { raw_days })
+            .unwrap_or_default()
+    }
+
+    /// Local midnight before which raw records are compacted, or None when
+    /// the policy keeps everything. Whole days only, so today stays raw.
+    pub fn cutoff(&self, now: DateTime<Local>) -> Option<NaiveDateTime> {
+        if self.raw_days == 0 {
+            return None;
+        }
+        let first_kept_day = now.date_naive() - Duration::days(self.raw_days as i64 - 1);
+        Some(first_kept_day.and_time(NaiveTime::MIN))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct RetentionSummary {
+    pub records_compacted: usize,
+    pub rollups_written: usize,
+}
+
+/// Compact every raw record older than the policy's cutoff, one day per
+/// transaction so a long backlog never has to sit in memory at once.
+/// Freed pages are reused by SQLite, so the file stops growing rather than shrinking.
+pub fn apply(db: &Database, policy: RetentionPolicy, now: DateTime<Local>) -> rusqlite::Result<RetentionSummary> {
+    let mut summary = RetentionSummary::default();
+    let cutoff = match policy.cutoff(now) {
+        Some(cutoff) => cutoff,
+        None => return Ok(summary),
+    };
+
+    while let Some(oldest) = db.oldest_record_datetime()? {
+        let oldest = match NaiveDateTime::parse_from_str(&oldest, DATETIME_FORMAT) {
+            Ok(oldest) => oldest,
+            Err(e) => {
+                eprintln!("Retention stopped at unparseable datetime '{}': {}", oldest, e);
+                break;
+            }
+        };
+        if oldest >= cutoff {
+            break;
+        }
+
+        let day_start = oldest.date().and_time(NaiveTime::MIN);
+        let day_end = (day_start + Duration::days(1)).min(cutoff);
+        let start = day_start.format(DATETIME_FORMAT).to_string();
+        let end = day_end.format(DATETIME_FORMAT).to_string();
+
+        let rollups = rollup_records(&db.get_records_in(&start, &end)?);
+        let deleted = db.replace_records_with_rollups(&start, &end, &rollups)?;
+        if deleted == 0 {
+            break;
+        }
+        summary.records_compacted += deleted;
+        summary.rollups_written += rollups.len();
+    }
+
+    Ok(summary)
+}
+
+fn count_mut<'a>(rollup: &'a mut EmotionRollup, emotion: &str) -> Option<&'a mut i64> {
+    match emotion {
+        "happy" => Some(&mut rollup.happy_count),
+        "calm" => Some(&mut rollup.calm_count),
+        "worried" => Some(&mut rollup.worried_count),
+        "tired" => Some(&mut rollup.tired_count),
+        _ => None,
+    }
+}
+
+/// Group records into half hours of their local datetime. Focus time between
+/// two records is credited to the half hour of the later one.
+pub fn rollup_records(records: &[EmotionRecord]) -> Vec<EmotionRollup> {
+    let mut sorted: Vec<&EmotionRecord> = records.iter().collect();
+    sorted.sort_by_key(|r| r.timestamp);
+
+    // Half-hour start -> (rollup, sum of confidences)
+    let mut buckets: BTreeMap<NaiveDateTime, (EmotionRollup, f64)> = BTreeMap::new();
+    let mut last_focused: Option<i64> = None;
+
+    for record in sorted {
+        let datetime = match NaiveDateTime::parse_from_str(&record.datetime, DATETIME_FORMAT) {
+            Ok(datetime) => datetime,
+            Err(_) => continue,
+        };
+        let minute = if datetime.minute() < 30 { 0 } else { 30 };
+        let bucket_start = datetime.date().and_time(NaiveTime::MIN)
+            + Duration::hours(datetime.hour() as i64)
+            + Duration::minutes(minute);
+
+        let (rollup, confidence_sum) = buckets.entry(bucket_start).or_insert_with(|| {
+            (
+                EmotionRollup {
+                    timestamp: record.timestamp - (datetime - bucket_start).num_seconds(),
+                    datetime: bucket_start.format(DATETIME_FORMAT).to_string(),
+                    happy_count: 0,
+                    calm_count: 0,
+                    worried_count: 0,
+                    tired_count: 0,
+                    mean_confidence: 0.0,
+                    focus_minutes: 0.0,
+                },
+                0.0,
+            )
+        });
+
+        match count_mut(rollup, &record.mapped_emotion) {
+            Some(count) => *count += 1,
+            None => continue,
+        }
+        *confidence_sum += record.fer_confidence;
+
+        if record.has_face && !record.is_away {
+            if let Some(last) = last_focused {
+                let gap = record.timestamp - last;
+                if gap <= MAX_FOCUS_GAP_SECONDS {
+                    rollup.focus_minutes += gap as f64 / 60.0;
+                }
+            }
+            last_focused = Some(record.timestamp);
+        } else {
+            last_focused = None;
+        }
+    }
+
+    buckets
+        .into_values()
+        .filter(|(rollup, _)| rollup.sample_count() > 0)
+        .map(|(mut rollup, confidence_sum)| {
+            rollup.mean_confidence = confidence_sum / rollup.sample_count() as f64;
+            rollup
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::path::PathBuf;
+
+    fn record(at: DateTime<Local>, emotion: &str, confidence: f64, focused: bool) -> EmotionRecord {
+        EmotionRecord {
+            id: None,
+            timestamp: at.timestamp(),
+            datetime: at.format(DATETIME_FORMAT).to_string(),
+            fer_emotion: emotion.to_string(),
+            fer_confidence: confidence,
+            deepface_emotion: None,
+            deepface_confidence: None,
+            mapped_emotion: emotion.to_string(),
+            work_minutes: 0.0,
+            is_away: !focused,
+            has_face: focused,
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32, second: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 1, day, hour, minute, second).unwrap()
+    }
+
+    #[test]
+    fn test_cutoff_keeps_whole_days() {
+        let now = at(31, 15, 20, 0);
+        assert_eq!(RetentionPolicy { raw_days: 0 }.cutoff(now), None);
+        assert_eq!(
+            RetentionPolicy { raw_days: 1 }.cutoff(now).unwrap().to_string(),
+            "2025-01-31 00:00:00"
+        );
+        assert_eq!(
+            RetentionPolicy { raw_days: 30 }.cutoff(now).unwrap().to_string(),
+            "2025-01-02 00:00:00"
+        );
+    }
+
+    #[test]
+    fn test_rollup_counts_confidence_and_focus() {
+        let records = vec![
+            record(at(1, 9, 0, 0), "happy", 0.8, true),
+            record(at(1, 9, 0, 30), "happy", 0.6, true),
+            record(at(1, 9, 1, 30), "calm", 0.7, true),
+            // Away breaks the focus streak
+            record(at(1, 9, 10, 0), "tired", 0.5, false),
+            record(at(1, 9, 29, 50), "worried", 0.4, true),
+            // Credited to 9:30 even though the streak started at 9:29:50
+            record(at(1, 9, 30, 20), "calm", 0.9, true),
+        ];
+
+        let rollups = rollup_records(&records);
+        assert_eq!(rollups.len(), 2);
+
+        let first = &rollups[0];
+        assert_eq!(first.datetime, "2025-01-01 09:00:00");
+        assert_eq!(first.timestamp, at(1, 9, 0, 0).timestamp());
+        assert_eq!((first.happy_count, first.calm_count, first.worried_count, first.tired_count), (2, 1, 1, 1));
+        assert!((first.mean_confidence - 0.6).abs() < 1e-9);
+        assert!((first.focus_minutes - 1.5).abs() < 1e-9);
+
+        let second = &rollups[1];
+        assert_eq!(second.datetime, "2025-01-01 09:30:00");
+        assert_eq!(second.sample_count(), 1);
+        assert!((second.focus_minutes - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_apply_compacts_old_records_only() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.init().unwrap();
+
+        for r in [
+            record(at(1, 9, 0, 0), "happy", 0.8, true),
+            record(at(1, 9, 0, 30), "calm", 0.6, true),
+            record(at(2, 23, 45, 0), "tired", 0.5, true),
+            record(at(20, 10, 0, 0), "worried", 0.7, true),
+        ] {
+            db.insert_record(&r).unwrap();
+        }
+
+        let policy = RetentionPolicy { raw_days: 10 };
+        let now = at(20, 12, 0, 0);
+        let summary = apply(&db, policy, now).unwrap();
+        assert_eq!(summary, RetentionSummary { records_compacted: 3, rollups_written: 2 });
+
+        let raw = db.get_records_by_date_range("2025-01-01 00:00:00", "2025-01-31 23:59:59").unwrap();
+        assert_eq!(raw.len(), 1);
+        assert_eq!(raw[0].mapped_emotion, "worried");
+
+        let rollups = db.get_rollups_by_date_range("2025-01-01 00:00:00", "2025-01-31 23:59:59").unwrap();
+        assert_eq!(rollups.len(), 2);
+        assert_eq!(rollups[0].sample_count(), 2);
+        assert_eq!(rollups[1].datetime, "2025-01-02 23:30:00");
+
+        // Nothing left to do, and a late record merges into its existing half hour
+        assert_eq!(apply(&db, policy, now).unwrap(), RetentionSummary::default());
+        db.insert_record(&record(at(1, 9, 15, 0), "calm", 0.1, false)).unwrap();
+        apply(&db, policy, now).unwrap();
+        let merged = &db.get_rollups_by_date_range("2025-01-01 09:00:00", "2025-01-01 09:00:00").unwrap()[0];
+        assert_eq!((merged.happy_count, merged.calm_count), (1, 2));
+        assert!((merged.mean_confidence - 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,222 @@
+use serde::Serialize;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+/// Detection interval until the user changes it
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+pub const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ScheduleStatus {
+    #[ts(type = "number")]
+    pub interval_ms: u64,
+    pub paused: bool,
+    pub running: bool,
+}
+
+struct ScheduleState {
+    interval: Duration,
+    paused: bool,
+    stopped: bool,
+    next_due: Instant,
+}
+
+/// Runs ticks on a background thread at a configurable interval, independent
+/// of whether any webview is visible. Drives emotion detection, the daily
+/// retention pass (24 hour interval) and the sound sleep timer (1 s interval).
+pub struct IntervalScheduler {
+    state: Arc<(Mutex<ScheduleState>, Condvar)>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl IntervalScheduler {
+    pub fn new(interval: Duration) -> Self {
+        IntervalScheduler {
+            state: Arc::new((
+                Mutex::new(ScheduleState {
+                    interval: interval.max(MIN_INTERVAL),
+                    paused: false,
+                    stopped: false,
+                    next_due: Instant::now(),
+                }),
+                Condvar::new(),
+            )),
+            handle: Mutex::new(None),
+        }
+    }
+
+    /// Start calling `tick` on the scheduler thread; the first tick runs immediately
+    pub fn start<F>(&self, tick: F) -> Result<(), String>
+    where
+        F: Fn() + Send + 'static,
+    {
+        let mut handle_guard = self.handle.lock()
+            .map_err(|e| format!("Scheduler lock poisoned: {}", e))?;
+        if handle_guard.is_some() {
+            return Ok(());
+        }
+
+        {
+            let mut state = self.state.0.lock()
+                .map_err(|e| format!("Scheduler lock poisoned: {}", e))?;
+            state.stopped = false;
+            state.next_due = Instant::now();
+        }
+
+        let shared = Arc::clone(&self.state);
+        *handle_guard = Some(thread::spawn(move || run(shared, tick)));
+        Ok(())
+    }
+
+    pub fn pause(&self) {
+        self.update(|state| state.paused = true);
+    }
+
+    /// Resume and tick right away rather than waiting a full interval
+    pub fn resume(&self) {
+        self.update(|state| {
+            state.paused = false;
+            state.next_due = Instant::now();
+        });
+    }
+
+    pub fn set_interval(&self, interval: Duration) -> Result<(), String> {
+        if interval < MIN_INTERVAL {
+            return Err(format!("Detection interval must be at least {} ms", MIN_INTERVAL.as_millis()));
+        }
+        self.update(|state| {
+            // Keep the phase of the current wait, just stretch or shrink it
+            let last_run = state.next_due.checked_sub(state.interval).unwrap_or_else(Instant::now);
+            state.interval = interval;
+            state.next_due = last_run + interval;
+        });
+        Ok(())
+    }
+
+    pub fn status(&self) -> ScheduleStatus {
+        let running = self.handle.lock().map(|h| h.is_some()).unwrap_or(false);
+        match self.state.0.lock() {
+            Ok(state) => ScheduleStatus {
+                interval_ms: state.interval.as_millis() as u64,
+                paused: state.paused,
+                running,
+            },
+            Err(_) => ScheduleStatus { interval_ms: 0, paused: true, running },
+        }
+    }
+
+    /// Stop the thread, waiting for an in-flight tick to finish
+    pub fn stop(&self) {
+        self.update(|state| state.stopped = true);
+        if let Ok(mut handle_guard) = self.handle.lock() {
+            if let Some(handle) = handle_guard.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+
+    fn update<F: FnOnce(&mut ScheduleState)>(&self, f: F) {
+        let (lock, condvar) = &*self.state;
+        if let Ok(mut state) = lock.lock() {
+            f(&mut state);
+            condvar.notify_all();
+        }
+    }
+}
+
+impl Drop for IntervalScheduler {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn run<F: Fn()>(shared: Arc<(Mutex<ScheduleState>, Condvar)>, tick: F) {
+    let (lock, condvar) = &*shared;
+    loop {
+        {
+            let mut state = match lock.lock() {
+                Ok(state) => state,
+                Err(_) => return,
+            };
+            // Sleep until due, re-checking whenever pause/resume/interval changes wake us
+            loop {
+                if state.stopped {
+                    return;
+                }
+                if state.paused {
+                    state = match condvar.wait(state) {
+                        Ok(state) => state,
+                        Err(_) => return,
+                    };
+                    continue;
+                }
+                let now = Instant::now();
+                if now >= state.next_due {
+                    break;
+                }
+                let wait = state.next_due - now;
+                state = match condvar.wait_timeout(state, wait) {
+                    Ok((state, _)) => state,
+                    Err(_) => return,
+                };
+            }
+            state.next_due = Instant::now() + state.interval;
+        }
+
+        tick();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn counting_scheduler(interval: Duration) -> (IntervalScheduler, Arc<AtomicUsize>) {
+        let scheduler = IntervalScheduler::new(interval);
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&count);
+        scheduler.start(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }).unwrap();
+        (scheduler, count)
+    }
+
+    #[test]
+    fn test_ticks_immediately_then_waits() {
+        let (scheduler, count) = counting_scheduler(Duration::from_secs(60));
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        scheduler.stop();
+        assert!(!scheduler.status().running);
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let (scheduler, count) = counting_scheduler(Duration::from_secs(60));
+        thread::sleep(Duration::from_millis(50));
+        scheduler.pause();
+        assert!(scheduler.status().paused);
+
+        scheduler.resume();
+        thread::sleep(Duration::from_millis(100));
+        // Resuming triggers a detection without waiting for the interval
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+        scheduler.stop();
+    }
+
+    #[test]
+    fn test_set_interval_validates_and_applies() {
+        let (scheduler, count) = counting_scheduler(Duration::from_secs(60));
+        assert!(scheduler.set_interval(Duration::from_millis(10)).is_err());
+
+        scheduler.set_interval(MIN_INTERVAL).unwrap();
+        assert_eq!(scheduler.status().interval_ms, 1000);
+        thread::sleep(Duration::from_millis(1300));
+        assert!(count.load(Ordering::SeqCst) >= 2);
+        scheduler.stop();
+    }
+}
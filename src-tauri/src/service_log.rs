@@ -0,0 +1,180 @@
+use chrono::Local;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use ts_rs::TS;
+
+pub const DEFAULT_CAPACITY: usize = 500;
+const LOG_FILE_NAME: &str = "emotion_service.log";
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+// emotion_service.log plus emotion_service.log.1 .. .3
+const MAX_ROTATED_FILES: usize = 3;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct LogLine {
+    pub timestamp: String,
+    pub stream: String,   // "stderr", or "stdout" for non-protocol noise
+    pub pid: Option<u32>,
+    pub line: String,
+}
+
+struct LogFile {
+    dir: PathBuf,
+    file: Option<File>,
+    size: u64,
+}
+
+/// Recent output of the Python emotion service: an in-memory ring buffer
+/// mirrored to a size-rotated log file once a directory is configured
+pub struct ServiceLog {
+    lines: Mutex<VecDeque<LogLine>>,
+    capacity: usize,
+    file: Mutex<Option<LogFile>>,
+}
+
+impl ServiceLog {
+    pub fn new(capacity: usize) -> Self {
+        ServiceLog {
+            lines: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            file: Mutex::new(None),
+        }
+    }
+
+    pub fn set_dir(&self, dir: PathBuf) -> Result<(), String> {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create log directory: {}", e))?;
+        let size = fs::metadata(dir.join(LOG_FILE_NAME)).map(|m| m.len()).unwrap_or(0);
+
+        let mut file_guard = self.file.lock()
+            .map_err(|e| format!("Log file lock poisoned: {}", e))?;
+        *file_guard = Some(LogFile { dir, file: None, size });
+        Ok(())
+    }
+
+    pub fn push(&self, stream: &str, pid: Option<u32>, line: &str) {
+        let entry = LogLine {
+            timestamp: Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            stream: stream.to_string(),
+            pid,
+            line: line.trim_end().to_string(),
+        };
+
+        if let Ok(mut file_guard) = self.file.lock() {
+            if let Some(log_file) = file_guard.as_mut() {
+                if let Err(e) = log_file.write(&entry) {
+                    eprintln!("Failed to write emotion service log: {}", e);
+                }
+            }
+        }
+
+        if let Ok(mut lines) = self.lines.lock() {
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(entry);
+        }
+    }
+
+    /// The most recent `limit` lines, oldest first
+    pub fn recent(&self, limit: usize) -> Vec<LogLine> {
+        match self.lines.lock() {
+            Ok(lines) => {
+                let skip = lines.len().saturating_sub(limit);
+                lines.iter().skip(skip).cloned().collect()
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+impl LogFile {
+    fn write(&mut self, entry: &LogLine) -> std::io::Result<()> {
+        if self.size >= MAX_LOG_BYTES {
+            self.rotate()?;
+        }
+
+        if self.file.is_none() {
+            self.file = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.dir.join(LOG_FILE_NAME))?,
+            );
+        }
+
+        let text = format!(
+            "{} [{}] [pid {}] {}\n",
+            entry.timestamp,
+            entry.stream,
+            entry.pid.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string()),
+            entry.line
+        );
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(text.as_bytes())?;
+            self.size += text.len() as u64;
+        }
+        Ok(())
+    }
+
+    /// Shift .log -> .log.1 -> .log.2 ..., dropping the oldest
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file = None;
+        let path = |n: usize| {
+            if n == 0 {
+                self.dir.join(LOG_FILE_NAME)
+            } else {
+                self.dir.join(format!("{}.{}", LOG_FILE_NAME, n))
+            }
+        };
+
+        let _ = fs::remove_file(path(MAX_ROTATED_FILES));
+        for n in (0..MAX_ROTATED_FILES).rev() {
+            if path(n).exists() {
+                fs::rename(path(n), path(n + 1))?;
+            }
+        }
+        self.size = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_keeps_latest_lines() {
+        let log = ServiceLog::new(3);
+        for i in 0..5 {
+            log.push("stderr", Some(42), &format!("line {}\n", i));
+        }
+
+        let lines: Vec<String> = log.recent(10).into_iter().map(|l| l.line).collect();
+        assert_eq!(lines, vec!["line 2", "line 3", "line 4"]);
+        assert_eq!(log.recent(1)[0].line, "line 4");
+    }
+
+    #[test]
+    fn test_log_file_rotates() {
+        let dir = std::env::temp_dir().join("moodpulse_service_log_test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let log = ServiceLog::new(10);
+        log.set_dir(dir.clone()).unwrap();
+        let long_line = "x".repeat(64 * 1024);
+        for _ in 0..40 {
+            log.push("stderr", None, &long_line);
+        }
+
+        assert!(dir.join(LOG_FILE_NAME).exists());
+        assert!(dir.join(format!("{}.1", LOG_FILE_NAME)).exists());
+        assert!(fs::metadata(dir.join(LOG_FILE_NAME)).unwrap().len() <= MAX_LOG_BYTES + 70 * 1024);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveTime, TimeZone};
+use serde::Serialize;
+use std::time::Duration;
+use ts_rs::TS;
+
+use crate::sounds::MixerState;
+
+/// How often the timer scheduler checks for an expired timer
+pub const TIMER_TICK: Duration = Duration::from_secs(1);
+
+/// Longest timer `after` accepts, in minutes
+pub const MAX_TIMER_MINUTES: u32 = 24 * 60;
+
+/// Stops ambient playback at `ends_at`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct SleepTimer {
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub ends_at: i64,
+    /// Fade out with the configured fade-out rather than cutting off
+    pub fade_out: bool,
+}
+
+impl SleepTimer {
+    /// Expire `minutes` from now
+    pub fn after(minutes: u32, fade_out: bool, now: DateTime<Local>) -> Result<Self, String> {
+        if minutes == 0 || minutes > MAX_TIMER_MINUTES {
+            return Err(format!("Timer must be 1-{} minutes, got {}", MAX_TIMER_MINUTES, minutes));
+        }
+        let ends_at = now + ChronoDuration::minutes(minutes as i64);
+        Ok(Self { ends_at: ends_at.timestamp(), fade_out })
+    }
+
+    /// Expire at the next local "HH:MM", today or else tomorrow
+    pub fn at(time: &str, fade_out: bool, now: DateTime<Local>) -> Result<Self, String> {
+        let time = NaiveTime::parse_from_str(time.trim(), "%H:%M")
+            .map_err(|_| format!("Invalid time '{}', expected HH:MM", time))?;
+        let mut date = now.date_naive();
+        if date.and_time(time) <= now.naive_local() {
+            date = date.succ_opt().ok_or("Timer date out of range")?;
+        }
+        let ends_at = Local.from_local_datetime(&date.and_time(time))
+            .earliest()
+            .ok_or_else(|| format!("{} does not exist on {} in this time zone", time.format("%H:%M"), date))?;
+        Ok(Self { ends_at: ends_at.timestamp(), fade_out })
+    }
+
+    pub fn remaining_secs(&self, now: DateTime<Local>) -> i64 {
+        (self.ends_at - now.timestamp()).max(0)
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>) -> bool {
+        now.timestamp() >= self.ends_at
+    }
+}
+
+/// What is playing and for how long; supersedes `is_white_noise_playing`
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct PlaybackStatus {
+    pub playing: bool,
+    pub mixer: MixerState,
+    pub timer: Option<SleepTimer>,
+    /// Seconds until the timer stops playback
+    #[ts(type = "number | null")]
+    pub remaining_secs: Option<i64>,
+}
+
+impl PlaybackStatus {
+    pub fn new(mixer: MixerState, timer: Option<SleepTimer>, now: DateTime<Local>) -> Self {
+        Self {
+            playing: !mixer.layers.is_empty(),
+            mixer,
+            timer,
+            remaining_secs: timer.map(|timer| timer.remaining_secs(now)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 1, 1, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_timer_after_minutes() {
+        let timer = SleepTimer::after(25, true, at(9, 0)).unwrap();
+        assert_eq!(timer.ends_at, at(9, 25).timestamp());
+        assert_eq!(timer.remaining_secs(at(9, 10)), 15 * 60);
+        assert!(!timer.is_due(at(9, 24)));
+        assert!(timer.is_due(at(9, 25)));
+        assert_eq!(timer.remaining_secs(at(10, 0)), 0);
+
+        assert!(SleepTimer::after(0, true, at(9, 0)).is_err());
+        assert!(SleepTimer::after(MAX_TIMER_MINUTES + 1, true, at(9, 0)).is_err());
+    }
+
+    #[test]
+    fn test_timer_at_wall_clock_time() {
+        let later_today = SleepTimer::at("23:30", false, at(22, 0)).unwrap();
+        assert_eq!(later_today.ends_at, at(23, 30).timestamp());
+        assert!(!later_today.fade_out);
+
+        let tomorrow = SleepTimer::at("07:00", true, at(22, 0)).unwrap();
+        assert_eq!(tomorrow.ends_at, Local.with_ymd_and_hms(2025, 1, 2, 7, 0, 0).unwrap().timestamp());
+        // A time equal to now means the same time tomorrow, not immediately
+        assert_eq!(SleepTimer::at("22:00", true, at(22, 0)).unwrap().remaining_secs(at(22, 0)), 24 * 3600);
+
+        assert!(SleepTimer::at("25:00", true, at(22, 0)).is_err());
+        assert!(SleepTimer::at("soon", true, at(22, 0)).is_err());
+    }
+
+    #[test]
+    fn test_playback_status() {
+        let idle = PlaybackStatus::new(MixerState { master_volume: 1.0, layers: Vec::new() }, None, at(9, 0));
+        assert!(!idle.playing);
+        assert_eq!(idle.remaining_secs, None);
+
+        let timer = SleepTimer::after(5, true, at(9, 0)).unwrap();
+        let mixer = MixerState { master_volume: 0.5, layers: vec![crate::sounds::MixLayer::new("rain", 1.0)] };
+        let status = PlaybackStatus::new(mixer, Some(timer), at(9, 1));
+        assert!(status.playing);
+        assert_eq!(status.remaining_secs, Some(4 * 60));
+    }
+}
@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use ts_rs::TS;
+
+/// Spectral color of a generated noise (see `audio::Noise`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseColor {
+    /// Flat spectrum
+    White,
+    /// Power falls 3 dB per octave
+    Pink,
+    /// Power falls 6 dB per octave
+    Brown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SoundKind {
+    /// File name inside the sounds resource directory
+    File(&'static str),
+    /// Synthesized while playing, so always available
+    Noise(NoiseColor),
+}
+
+/// An ambient track the app can play
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sound {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub emoji: &'static str,
+    pub kind: SoundKind,
+}
+
+/// The recordings in `src/assets/sounds`, bundled as the `sounds` resource,
+/// followed by the generated noises
+pub const SOUNDS: [Sound; 6] = [
+    Sound { id: "rain", name: "雨声", emoji: "🌧️", kind: SoundKind::File("rain.mp3") },
+    Sound { id: "fire", name: "火焰", emoji: "🔥", kind: SoundKind::File("fire.mp3") },
+    Sound { id: "cafe", name: "咖啡厅", emoji: "☕", kind: SoundKind::File("cafe.mp3") },
+    Sound { id: "white", name: "白噪声", emoji: "⚪", kind: SoundKind::Noise(NoiseColor::White) },
+    Sound { id: "pink", name: "粉红噪声", emoji: "🌸", kind: SoundKind::Noise(NoiseColor::Pink) },
+    Sound { id: "brown", name: "棕色噪声", emoji: "🟤", kind: SoundKind::Noise(NoiseColor::Brown) },
+];
+
+/// What `play_white_noise` plays
+pub const DEFAULT_SOUND: &str = "rain";
+
+/// Resource path of the sounds directory, as mapped in `tauri.conf.json`
+pub const RESOURCE_DIR: &str = "sounds";
+
+pub fn find(id: &str) -> Option<&'static Sound> {
+    SOUNDS.iter().find(|sound| sound.id == id)
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SoundInfo {
+    pub id: String,
+    pub name: String,
+    pub emoji: String,
+    /// Whether the file is present in the sounds directory
+    pub available: bool,
+    /// Synthesized rather than read from a file
+    pub generated: bool,
+}
+
+/// One sound playing in the mixer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MixLayer {
+    pub sound_id: String,
+    /// 0-1, scaled by the master volume
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl MixLayer {
+    pub fn new(sound_id: &str, volume: f32) -> Self {
+        Self { sound_id: sound_id.to_string(), volume, muted: false }
+    }
+
+    /// What the layer's sink actually plays at
+    pub fn effective_volume(&self, master_volume: f32) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume * master_volume
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MixerState {
+    pub master_volume: f32,
+    /// In the order they were added
+    pub layers: Vec<MixLayer>,
+}
+
+/// Longest fade the settings accept
+pub const MAX_FADE_MS: u64 = 30_000;
+
+/// How gently playback starts, stops and switches tracks; 0 means instant
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FadeSettings {
+    #[ts(type = "number")]
+    pub fade_in_ms: u64,
+    #[ts(type = "number")]
+    pub fade_out_ms: u64,
+    /// Used both ways when one track or mix replaces another
+    #[ts(type = "number")]
+    pub crossfade_ms: u64,
+}
+
+impl Default for FadeSettings {
+    fn default() -> Self {
+        Self { fade_in_ms: 2_000, fade_out_ms: 2_000, crossfade_ms: 3_000 }
+    }
+}
+
+impl FadeSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, ms) in [("fade_in_ms", self.fade_in_ms), ("fade_out_ms", self.fade_out_ms), ("crossfade_ms", self.crossfade_ms)] {
+            if ms > MAX_FADE_MS {
+                return Err(format!("{} must be at most {}, got {}", name, MAX_FADE_MS, ms));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn fade_in(&self) -> Duration {
+        Duration::from_millis(self.fade_in_ms)
+    }
+
+    pub fn fade_out(&self) -> Duration {
+        Duration::from_millis(self.fade_out_ms)
+    }
+
+    pub fn crossfade(&self) -> Duration {
+        Duration::from_millis(self.crossfade_ms)
+    }
+}
+
+/// Where the sound files live on disk
+#[derive(Debug, Clone)]
+pub struct SoundLibrary {
+    dir: PathBuf,
+}
+
+impl Default for SoundLibrary {
+    /// The source tree, for runs without a resolved resource directory
+    fn default() -> Self {
+        Self::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/assets/sounds"))
+    }
+}
+
+impl SoundLibrary {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Use the bundled resource directory when it exists, otherwise fall back
+    /// to the source tree
+    pub fn locate(resource_dir: Option<PathBuf>) -> Self {
+        match resource_dir {
+            Some(dir) if dir.is_dir() => Self::new(dir),
+            _ => Self::default(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The file behind `sound`, or None for generated noise
+    pub fn path(&self, sound: &Sound) -> Option<PathBuf> {
+        match sound.kind {
+            SoundKind::File(file) => Some(self.dir.join(file)),
+            SoundKind::Noise(_) => None,
+        }
+    }
+
+    /// The catalog in display order
+    pub fn list(&self) -> Vec<SoundInfo> {
+        SOUNDS.iter()
+            .map(|sound| SoundInfo {
+                id: sound.id.to_string(),
+                name: sound.name.to_string(),
+                emoji: sound.emoji.to_string(),
+                available: match self.path(sound) {
+                    Some(path) => path.is_file(),
+                    None => true,
+                },
+                generated: matches!(sound.kind, SoundKind::Noise(_)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_find_sound() {
+        assert_eq!(find("fire").map(|s| s.kind), Some(SoundKind::File("fire.mp3")));
+        assert_eq!(find("pink").map(|s| s.kind), Some(SoundKind::Noise(NoiseColor::Pink)));
+        assert!(find(DEFAULT_SOUND).is_some());
+        assert!(find("ocean").is_none());
+    }
+
+    #[test]
+    fn test_effective_volume() {
+        let mut layer = MixLayer::new("rain", 0.5);
+        assert_eq!(layer.effective_volume(0.8), 0.4);
+        layer.muted = true;
+        assert_eq!(layer.effective_volume(0.8), 0.0);
+    }
+
+    #[test]
+    fn test_fade_settings_validation() {
+        assert!(FadeSettings::default().validate().is_ok());
+        let instant = FadeSettings { fade_in_ms: 0, fade_out_ms: 0, crossfade_ms: 0 };
+        assert!(instant.validate().is_ok());
+        assert_eq!(instant.crossfade(), Duration::ZERO);
+        assert!(FadeSettings { crossfade_ms: MAX_FADE_MS + 1, ..instant }.validate().is_err());
+    }
+
+    #[test]
+    fn test_source_tree_ships_every_sound() {
+        let library = SoundLibrary::default();
+        for info in library.list() {
+            assert!(info.available, "{} missing from {}", info.id, library.dir().display());
+        }
+    }
+
+    #[test]
+    fn test_locate_prefers_resource_dir() {
+        let dir = std::env::temp_dir().join("moodpulse_sounds");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("cafe.mp3"), b"").unwrap();
+
+        let library = SoundLibrary::locate(Some(dir.clone()));
+        assert_eq!(library.dir(), dir);
+        let available: Vec<String> = library.list().into_iter()
+            .filter(|info| info.available && !info.generated)
+            .map(|info| info.id)
+            .collect();
+        assert_eq!(available, ["cafe"]);
+
+        let missing = SoundLibrary::locate(Some(dir.join("missing")));
+        assert_eq!(missing.dir(), SoundLibrary::default().dir());
+    }
+}
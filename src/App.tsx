@@ -4,6 +4,7 @@ import type { PetState, InteractionState } from './components/Pet'
 import { HoverToolbar } from './components/HoverToolbar'
 import { useEmotionDetection } from './hooks/useEmotionDetection'
 import { useEmotionStore } from './stores/emotionStore'
+import type { Emotion } from './stores/emotionStore'
 import { appWindow } from '@tauri-apps/api/window'
 import { WebviewWindow } from '@tauri-apps/api/window'
 import { Minimize2, X } from 'lucide-react'
@@ -23,7 +24,7 @@ export default function App() {
     if (emotionData?.emotions?.[0] && !isPaused && !privacyMode) {
       const emotion = emotionData.emotions[0]
       setPetState(emotion.emotion as PetState)
-      setCurrentEmotion(emotion.emotion)
+      setCurrentEmotion(emotion.emotion as Emotion)
     }
   }, [emotionData, setCurrentEmotion, isPaused, privacyMode])
 
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EmotionAnalysis } from "./EmotionAnalysis";
+import type { TimelinePoint } from "./TimelinePoint";
+
+export type AnalysisUpdate = { analysis: EmotionAnalysis, timeline: Array<TimelinePoint>, };
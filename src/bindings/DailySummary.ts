@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One day's analyses, kept in `daily_summaries` so past days can be shown
+ * without their raw records
+ */
+export type DailySummary = { 
+/**
+ * Local date, "YYYY-MM-DD"
+ */
+date: string, emotion_index: number, stress_level: number, total_records: number, valid_records: number, focus_sessions: number, focus_minutes: number, happy_count: number, calm_count: number, worried_count: number, tired_count: number, checkin_count: number, 
+/**
+ * When the summary was last recomputed
+ */
+updated_at: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One emotion estimate inside a detection response
+ */
+export type DetectedEmotion = { emotion: string, confidence: number, source: string | null, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DetectedEmotion } from "./DetectedEmotion";
+
+/**
+ * A detection response as produced by every EmotionBackend; this is also
+ * the payload of the `emotion-detected` event
+ */
+export type DetectionResult = { has_face: boolean, is_away: boolean, work_minutes: number, need_break_alert: boolean, emotions: Array<DetectedEmotion>, 
+/**
+ * When a replayed detection was originally recorded (unix ms); live
+ * backends leave it unset
+ */
+recorded_at_ms: number | null, };
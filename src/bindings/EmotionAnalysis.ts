@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type EmotionAnalysis = { emotion_index: number, stress_level: number, total_records: number, valid_records: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An emotion entered by the user or imported, stored in `emotion_entries`
+ * next to the detected `emotion_records`
+ */
+export type EmotionEntry = { id: number | null, 
+/**
+ * Filled in from `datetime` by `validate`
+ */
+timestamp: number, 
+/**
+ * Local "YYYY-MM-DD HH:MM:SS"; RFC 3339 is accepted on input
+ */
+datetime: string, emotion: string, confidence: number, source: string, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type EmotionRecord = { id: number | null, timestamp: number, datetime: string, fer_emotion: string, fer_confidence: number, deepface_emotion: string | null, deepface_confidence: number | null, mapped_emotion: string, work_minutes: number, is_away: boolean, has_face: boolean, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Half an hour of compacted `emotion_records`, kept after the raw rows
+ * have aged out (see retention.rs)
+ */
+export type EmotionRollup = { 
+/**
+ * Start of the half hour
+ */
+timestamp: number, 
+/**
+ * Start of the half hour, local "YYYY-MM-DD HH:MM:SS"
+ */
+datetime: string, happy_count: number, calm_count: number, worried_count: number, tired_count: number, 
+/**
+ * Mean fer_confidence of the compacted records
+ */
+mean_confidence: number, 
+/**
+ * Time spent in front of the camera, from consecutive focused records
+ */
+focus_minutes: number, };
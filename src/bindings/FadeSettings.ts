@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How gently playback starts, stops and switches tracks; 0 means instant
+ */
+export type FadeSettings = { fade_in_ms: number, fade_out_ms: number, 
+/**
+ * Used both ways when one track or mix replaces another
+ */
+crossfade_ms: number, };
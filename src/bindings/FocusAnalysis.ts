@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FocusAnalysis = { total_focus_sessions: number, current_focus_duration: number, is_currently_focusing: boolean, total_focus_time: number, };
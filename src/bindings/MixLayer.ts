@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One sound playing in the mixer
+ */
+export type MixLayer = { sound_id: string, 
+/**
+ * 0-1, scaled by the master volume
+ */
+volume: number, muted: boolean, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MixLayer } from "./MixLayer";
+
+export type MixerState = { master_volume: number, 
+/**
+ * In the order they were added
+ */
+layers: Array<MixLayer>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A self-reported mood, stored in `mood_checkins`
+ */
+export type MoodCheckin = { id: number | null, timestamp: number, datetime: string, emotion: string, 
+/**
+ * How strongly the emotion is felt, 1-10
+ */
+intensity: number, note: string | null, };
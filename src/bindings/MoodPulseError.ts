@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The error every Tauri command returns. Serializes as
+ * `{"code": "backend_timeout", "message": "..."}` so the frontend can branch
+ * on `code` (and localize it) while `message` keeps the English detail.
+ */
+export type MoodPulseError = { "code": "database", "message": string } | { "code": "backend_unavailable", "message": string } | { "code": "backend_timeout", "message": string } | { "code": "camera", "message": string } | { "code": "audio_device", "message": string } | { "code": "serialization", "message": string } | { "code": "io", "message": string } | { "code": "validation", "message": string };
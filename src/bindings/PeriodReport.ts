@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DayScore } from "./DayScore";
+import type { EmotionShare } from "./EmotionShare";
+import type { PeriodChange } from "./PeriodChange";
+import type { WeekSummary } from "./WeekSummary";
+
+export type PeriodReport = { start_date: string, end_date: string, days: Array<DayScore>, best_day: DayScore | null, worst_day: DayScore | null, average_emotion_index: number | null, average_stress_level: number | null, emotion_distribution: Array<EmotionShare>, dominant_emotion: string | null, total_focus_minutes: number, focus_sessions: number, weeks: Array<WeekSummary>, change: PeriodChange | null, };
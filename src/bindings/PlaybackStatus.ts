@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MixerState } from "./MixerState";
+import type { SleepTimer } from "./SleepTimer";
+
+/**
+ * What is playing and for how long; supersedes `is_white_noise_playing`
+ */
+export type PlaybackStatus = { playing: boolean, mixer: MixerState, timer: SleepTimer | null, 
+/**
+ * Seconds until the timer stops playback
+ */
+remaining_secs: number | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How long raw `emotion_records` are kept before they are compacted into
+ * half-hour rollups
+ */
+export type RetentionPolicy = { 
+/**
+ * Days of raw records to keep, counting today; 0 keeps them forever
+ */
+raw_days: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackendAvailability } from "./BackendAvailability";
+
+/**
+ * What emotion_service.py reports in its hello response
+ */
+export type ServiceInfo = { protocol_version: number, service_version: string, backends: BackendAvailability, camera_available: boolean, errors: Array<string>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Lifecycle state of the Python emotion service
+ */
+export type ServiceState = "starting" | "ready" | "crashed" | "backing_off" | "stopped";
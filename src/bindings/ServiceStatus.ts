@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ServiceInfo } from "./ServiceInfo";
+import type { ServiceState } from "./ServiceState";
+
+export type ServiceStatus = { state: ServiceState, pid: number | null, restart_attempts: number, last_error: string | null, service_info: ServiceInfo | null, };
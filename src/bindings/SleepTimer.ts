@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Stops ambient playback at `ends_at`
+ */
+export type SleepTimer = { 
+/**
+ * Unix seconds
+ */
+ends_at: number, 
+/**
+ * Fade out with the configured fade-out rather than cutting off
+ */
+fade_out: boolean, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SoundInfo = { id: string, name: string, emoji: string, 
+/**
+ * Whether the file is present in the sounds directory
+ */
+available: boolean, 
+/**
+ * Synthesized rather than read from a file
+ */
+generated: boolean, };
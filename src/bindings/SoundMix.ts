@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MixLayer } from "./MixLayer";
+
+/**
+ * A named set of mixer layers, stored in `sound_mixes`
+ */
+export type SoundMix = { name: string, master_volume: number, layers: Array<MixLayer>, updated_at: number, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PeriodChange } from "./PeriodChange";
+
+export type WeekSummary = { start_date: string, end_date: string, average_emotion_index: number | null, average_stress_level: number | null, focus_minutes: number, change: PeriodChange | null, };
@@ -3,6 +3,7 @@ import { useEmotionTimeline } from '../hooks/useEmotionTimeline'
 
 interface EmotionChartProps {
   onClose: () => void
+  date?: string  // YYYY-MM-DD，不传则为今天
 }
 
 // 自定义节点组件 - 心跳动画
@@ -20,13 +21,13 @@ const PulseDot = (props: any) => {
   );
 };
 
-const EmotionChart = ({ onClose }: EmotionChartProps) => {
-  const { timeline, loading, error } = useEmotionTimeline(30000);
+const EmotionChart = ({ onClose, date }: EmotionChartProps) => {
+  const { timeline, loading, error } = useEmotionTimeline(0, date);
 
   return (
     <div className="emotion-chart-container bg-white rounded-2xl p-6 shadow-xl max-w-2xl w-full">
       <div className="chart-header mb-6">
-        <h2 className="text-2xl font-bold text-text-dark">今天的情绪旅程 🌈</h2>
+        <h2 className="text-2xl font-bold text-text-dark">{date ? `${date} 的情绪旅程 🌈` : '今天的情绪旅程 🌈'}</h2>
       </div>
 
       <div className="chart-content mb-6 relative">
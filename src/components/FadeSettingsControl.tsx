@@ -0,0 +1,51 @@
+import { useState, useEffect } from 'react';
+import { getFadeSettings, setFadeSettings, type FadeSettings } from '../utils/tauriApi';
+
+const FIELDS: { key: keyof FadeSettings; label: string }[] = [
+  { key: 'fade_in_ms', label: '淡入' },
+  { key: 'fade_out_ms', label: '淡出' },
+  { key: 'crossfade_ms', label: '切换' },
+];
+
+// 渐变时长，按秒调节（0-10 秒）
+export function FadeSettingsControl() {
+  const [settings, setSettings] = useState<FadeSettings | null>(null);
+
+  useEffect(() => {
+    getFadeSettings().then(setSettings).catch((error) => {
+      console.error('Failed to load fade settings:', error);
+    });
+  }, []);
+
+  if (!settings) return null;
+
+  const update = async (key: keyof FadeSettings, seconds: number) => {
+    const next = { ...settings, [key]: Math.round(seconds * 1000) };
+    setSettings(next);
+    try {
+      setSettings(await setFadeSettings(next));
+    } catch (error) {
+      console.error('Failed to save fade settings:', error);
+    }
+  };
+
+  return (
+    <div className="bg-white/80 rounded-lg p-2.5 space-y-1.5">
+      {FIELDS.map(({ key, label }) => (
+        <div key={key} className="flex items-center gap-2 text-xs text-gray-700">
+          <span className="w-8 font-medium">{label}</span>
+          <input
+            type="range"
+            min="0"
+            max="10"
+            step="0.5"
+            value={settings[key] / 1000}
+            onChange={(e) => update(key, parseFloat(e.target.value))}
+            className="flex-1 h-1.5 bg-gray-200 rounded-lg appearance-none cursor-pointer accent-rose-400"
+          />
+          <span className="w-8 text-right text-gray-600">{settings[key] / 1000}s</span>
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,151 @@
+import { TrendingUp, TrendingDown, Minus } from 'lucide-react';
+import { usePeriodReport } from '../hooks/usePeriodReport';
+import type { PeriodChange } from '../hooks/usePeriodReport';
+import { emotionToColor, emotionToEmoji, type Emotion } from '../utils/emotionUtils';
+
+interface PeriodReportViewProps {
+  startDate: string;
+  endDate: string;
+}
+
+const EMOTION_LABELS: Record<string, string> = {
+  happy: '开心',
+  calm: '平静',
+  worried: '焦虑',
+  tired: '疲惫',
+};
+
+// 环比变化；stress 越低越好，颜色反过来
+function Delta({ value, unit = '', inverse = false }: { value: number; unit?: string; inverse?: boolean }) {
+  if (Math.abs(value) < 0.01) {
+    return (
+      <span className="inline-flex items-center gap-0.5 text-[10px] text-gray-400">
+        <Minus size={10} />持平
+      </span>
+    );
+  }
+  const good = inverse ? value < 0 : value > 0;
+  const Icon = value > 0 ? TrendingUp : TrendingDown;
+  return (
+    <span className={`inline-flex items-center gap-0.5 text-[10px] font-medium ${good ? 'text-emerald-500' : 'text-rose-400'}`}>
+      <Icon size={10} />
+      {value > 0 ? '+' : ''}{value.toFixed(1)}{unit}
+    </span>
+  );
+}
+
+function ChangeRow({ change }: { change: PeriodChange | null }) {
+  if (!change) {
+    return <span className="text-[10px] text-gray-400">无上期数据</span>;
+  }
+  return (
+    <span className="flex gap-2">
+      <Delta value={change.emotion_index} />
+      <Delta value={change.stress_level} unit="%" inverse />
+      <Delta value={change.focus_minutes} unit="分" />
+    </span>
+  );
+}
+
+// 日期只显示 MM-DD
+const shortDate = (date: string) => date.slice(5);
+
+export function PeriodReportView({ startDate, endDate }: PeriodReportViewProps) {
+  const { report, loading, error } = usePeriodReport(startDate, endDate);
+
+  if (loading && !report) {
+    return <div className="text-center text-xs text-gray-400 py-10">加载中...</div>;
+  }
+  if (error || !report) {
+    return <div className="text-center text-xs text-red-400 py-10">{error ?? '报告生成失败'}</div>;
+  }
+  if (report.days.length === 0) {
+    return <div className="text-center text-xs text-gray-400 py-10">这段时间暂无数据</div>;
+  }
+
+  const hours = Math.floor(report.total_focus_minutes / 60);
+  const minutes = Math.round(report.total_focus_minutes % 60);
+
+  return (
+    <div className="space-y-4">
+      {/* 核心指标 */}
+      <div className="grid grid-cols-2 gap-3">
+        <div className="bg-gradient-to-br from-[#fff0f5] to-[#ffe4e6] p-4 rounded-2xl border border-rose-100 shadow-sm">
+          <p className="text-xs font-bold text-rose-400 mb-1">平均情绪</p>
+          <div className="text-3xl font-bold text-[#883446] mb-1" style={{ fontVariantNumeric: 'tabular-nums' }}>
+            {report.average_emotion_index?.toFixed(1) ?? '-'}
+          </div>
+          {report.change && <Delta value={report.change.emotion_index} />}
+        </div>
+        <div className="bg-gradient-to-br from-[#f0f9ff] to-[#e0f2fe] p-4 rounded-2xl border border-sky-100 shadow-sm">
+          <p className="text-xs font-bold text-sky-500 mb-1">累计专注</p>
+          <div className="text-3xl font-bold text-[#0c4a6e] mb-1" style={{ fontVariantNumeric: 'tabular-nums' }}>
+            {hours > 0 ? `${hours}h${minutes}m` : `${minutes}m`}
+          </div>
+          <div className="text-[10px] font-medium text-gray-500">
+            专注 {report.focus_sessions} 次
+            {report.change && <> · <Delta value={report.change.focus_minutes} unit="分/天" /></>}
+          </div>
+        </div>
+      </div>
+
+      {/* 每日情绪指数 */}
+      <div className="bg-white/60 backdrop-blur-sm p-5 rounded-2xl border border-white/60 shadow-sm">
+        <p className="text-sm font-bold text-[#574c4f] mb-3">每日情绪</p>
+        <div className="flex items-end gap-1 h-24">
+          {report.days.map((day) => (
+            <div key={day.date} className="flex-1 flex flex-col items-center gap-1" title={`${day.date} 压力 ${Math.round(day.stress_level)}%`}>
+              <div
+                className={`w-full rounded-t-md ${
+                  day.date === report.best_day?.date
+                    ? 'bg-emerald-300'
+                    : day.date === report.worst_day?.date
+                      ? 'bg-rose-300'
+                      : 'bg-amber-200'
+                }`}
+                style={{ height: `${day.emotion_index * 8}px` }}
+              />
+              <span className="text-[8px] text-gray-400">{shortDate(day.date)}</span>
+            </div>
+          ))}
+        </div>
+        <div className="flex justify-between mt-3 text-[10px] text-[#8c6a64] font-medium">
+          {report.best_day && <span>最佳 {shortDate(report.best_day.date)} · {report.best_day.emotion_index.toFixed(1)}</span>}
+          {report.worst_day && <span>最低 {shortDate(report.worst_day.date)} · {report.worst_day.emotion_index.toFixed(1)}</span>}
+        </div>
+      </div>
+
+      {/* 情绪分布 */}
+      <div className="bg-white/60 backdrop-blur-sm p-5 rounded-2xl border border-white/60 shadow-sm">
+        <p className="text-sm font-bold text-[#574c4f] mb-3">情绪分布</p>
+        <div className="space-y-2">
+          {report.emotion_distribution.map((share) => (
+            <div key={share.emotion} className="flex items-center gap-2 text-xs text-[#574c4f]">
+              <span className="w-14">{emotionToEmoji(share.emotion as Emotion)} {EMOTION_LABELS[share.emotion] ?? share.emotion}</span>
+              <div className="flex-1 h-2 bg-[#f5e6d3] rounded-full overflow-hidden">
+                <div
+                  className="h-full rounded-full"
+                  style={{ width: `${share.share * 100}%`, background: emotionToColor(share.emotion as Emotion) }}
+                />
+              </div>
+              <span className="w-10 text-right text-gray-500">{Math.round(share.share * 100)}%</span>
+            </div>
+          ))}
+        </div>
+      </div>
+
+      {/* 每周环比 */}
+      <div className="bg-white/60 backdrop-blur-sm p-5 rounded-2xl border border-white/60 shadow-sm">
+        <p className="text-sm font-bold text-[#574c4f] mb-3">每周变化</p>
+        <div className="space-y-2">
+          {report.weeks.map((week) => (
+            <div key={week.start_date} className="flex justify-between items-center text-xs text-[#574c4f]">
+              <span>{shortDate(week.start_date)} ~ {shortDate(week.end_date)}</span>
+              <ChangeRow change={week.change} />
+            </div>
+          ))}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,8 +1,9 @@
 import { useState, useEffect, useRef, useMemo } from "react";
 import { motion, AnimatePresence } from "framer-motion";
-import { Howl } from "howler";
 import { WebviewWindow } from '@tauri-apps/api/window';
 import { listen } from '@tauri-apps/api/event';
+import { useSounds } from '../hooks/useSounds';
+import { playSound as playBackendSound, stopSound, setSoundVolume } from '../utils/tauriApi';
 
 // 类型定义
 export type PetState =
@@ -33,24 +34,18 @@ export function PetCharacter({
   const [mousePos, setMousePos] = useState({ x: 0, y: 0 });
   const [localInteraction, setLocalInteraction] = useState<InteractionState>(null);
 
-  const soundRef = useRef<Howl | null>(null);
   const clickTimeoutRef = useRef<number | null>(null);
   const containerRef = useRef<HTMLDivElement | null>(null);
 
-  const sounds = [
-    { id: 'rain', name: '雨声', emoji: '🌧️', file: '/src/assets/sounds/rain.mp3' },
-    { id: 'ocean', name: '海浪', emoji: '🌊', file: '/src/assets/sounds/ocean.mp3' },
-    { id: 'forest', name: '森林', emoji: '🌲', file: '/src/assets/sounds/forest.mp3' },
-    { id: 'fire', name: '火焰', emoji: '🔥', file: '/src/assets/sounds/fire.mp3' },
-    { id: 'cafe', name: '咖啡厅', emoji: '☕', file: '/src/assets/sounds/cafe.mp3' },
-  ];
+  const sounds = useSounds();
 
   // 监听来自白噪音选择窗口的事件
   useEffect(() => {
     const unlistenSound = listen<{ sound: string; volume: number; isPlaying: boolean }>(
       'sound-selected',
       (event) => {
-        playSound(event.payload.sound);
+        setVolume(event.payload.volume);
+        playSound(event.payload.sound, event.payload.volume);
       }
     );
 
@@ -61,9 +56,15 @@ export function PetCharacter({
       }
     );
 
+    // 定时器到点后后端已停止播放
+    const unlistenTimer = listen('sound-timer-finished', () => {
+      setIsPlaying(false);
+    });
+
     return () => {
       unlistenSound.then(fn => fn());
       unlistenVolume.then(fn => fn());
+      unlistenTimer.then(fn => fn());
     };
   }, []);
 
@@ -83,32 +84,23 @@ export function PetCharacter({
     return () => window.removeEventListener('mousemove', handleMouseMove);
   }, []);
 
-  // 播放选中的音频
-  const playSound = (soundId: string) => {
-    // 停止当前播放
-    if (soundRef.current) {
-      soundRef.current.stop();
-      soundRef.current.unload();
+  // 播放选中的音频（由后端播放，替换当前曲目）
+  const playSound = async (soundId: string, soundVolume = volume) => {
+    try {
+      await playBackendSound(soundId, soundVolume);
+      setIsPlaying(true);
+      setCurrentSound(soundId);
+    } catch (error) {
+      console.error('Failed to play sound:', error);
     }
-
-    const soundData = sounds.find(s => s.id === soundId);
-    if (!soundData) return;
-
-    soundRef.current = new Howl({
-      src: [soundData.file],
-      loop: true,
-      volume: volume,
-    });
-
-    soundRef.current.play();
-    setIsPlaying(true);
-    setCurrentSound(soundId);
   };
 
   // 暂停播放
-  const pauseSound = () => {
-    if (soundRef.current) {
-      soundRef.current.stop();
+  const pauseSound = async () => {
+    try {
+      await stopSound();
+    } catch (error) {
+      console.error('Failed to stop sound:', error);
     }
     setIsPlaying(false);
   };
@@ -121,7 +113,7 @@ export function PetCharacter({
       playSound(currentSound);
     } else {
       // 如果没有选择音频，默认播放第一个
-      playSound(sounds[0].id);
+      playSound(sounds[0]?.id ?? 'rain');
     }
   };
 
@@ -169,9 +161,9 @@ export function PetCharacter({
   // 调节音量
   const handleVolumeChange = (newVolume: number) => {
     setVolume(newVolume);
-    if (soundRef.current) {
-      soundRef.current.volume(newVolume);
-    }
+    setSoundVolume(newVolume).catch((error) => {
+      console.error('Volume change error:', error);
+    });
   };
 
   // 点击宠物触发动画
@@ -197,10 +189,7 @@ export function PetCharacter({
   // 清理
   useEffect(() => {
     return () => {
-      if (soundRef.current) {
-        soundRef.current.stop();
-        soundRef.current.unload();
-      }
+      stopSound().catch(() => {});
       if (clickTimeoutRef.current) {
         clearTimeout(clickTimeoutRef.current);
       }
@@ -1,9 +1,19 @@
 import React from 'react';
-import { X, Activity, Brain, Zap } from 'lucide-react';
+import { X, Activity, Brain, Zap, ChevronLeft, ChevronRight, Download } from 'lucide-react';
 import EmotionChart from './EmotionChart';
 import { SnapshotCard } from './SnapshotCard';
+import { PeriodReportView } from './PeriodReportView';
 import { useEmotionAnalysis } from '../hooks/useEmotionAnalysis';
 import { useFocusAnalysis } from '../hooks/useFocusAnalysis';
+import { shiftDate, todayString } from '../utils/dateUtils';
+import { exportEmotionRecords } from '../utils/tauriApi';
+import { errorMessage } from '../utils/errors';
+
+type Period = 'day' | 'week' | 'month';
+
+// 各报告周期的天数，翻页时按此步长移动
+const PERIOD_DAYS: Record<Period, number> = { day: 1, week: 7, month: 30 };
+const PERIOD_LABELS: Record<Period, string> = { day: '日报', week: '周报', month: '月报' };
 
 interface ReportWindowProps {
   onClose: () => void;
@@ -31,8 +41,18 @@ function getStressLabel(stressLevel: number): string {
 }
 
 export function ReportWindow({ onClose }: ReportWindowProps) {
-  const { analysis, loading, error } = useEmotionAnalysis(30000);
-  const { focusData, loading: focusLoading } = useFocusAnalysis(30000);
+  // 正在查看的日期，可向前翻看历史
+  const [date, setDate] = React.useState(todayString());
+  const [period, setPeriod] = React.useState<Period>('day');
+  const isToday = date === todayString();
+  const periodStart = shiftDate(date, 1 - PERIOD_DAYS[period]);
+  // 向后翻页不超过今天
+  const nextDate = (() => {
+    const next = shiftDate(date, PERIOD_DAYS[period]);
+    return next > todayString() ? todayString() : next;
+  })();
+  const { analysis, loading, error } = useEmotionAnalysis(0, date);
+  const { focusData, loading: focusLoading } = useFocusAnalysis(0, date);
   const [countMood, setCountMood] = React.useState(0);
   const [countFocus, setCountFocus] = React.useState(0);
 
@@ -53,6 +73,18 @@ export function ReportWindow({ onClose }: ReportWindowProps) {
     stressLevel: stressLevel
   };
 
+  // 导出当前查看范围内的检测记录
+  const handleExport = async () => {
+    try {
+      const result = await exportEmotionRecords('csv', `${periodStart} 00:00:00`, `${date} 23:59:59`);
+      if (result) {
+        console.log(`Exported ${result.rows} records to ${result.path}`);
+      }
+    } catch (err) {
+      console.error('Failed to export emotion records:', errorMessage(err));
+    }
+  };
+
   // Animated counter effect
   React.useEffect(() => {
     const moodTarget = todayStats.avgMood * 10;
@@ -78,7 +110,7 @@ export function ReportWindow({ onClose }: ReportWindowProps) {
       clearInterval(moodInterval);
       clearInterval(focusInterval);
     };
-  }, []);
+  }, [emotionIndex, totalFocusTime]);
 
   const snapshots = [
     {
@@ -137,18 +169,63 @@ export function ReportWindow({ onClose }: ReportWindowProps) {
                   <span className="w-2 h-2 rounded-full bg-emerald-400 animate-pulse" />
                   <p className="text-xs text-[#8c6a64] font-medium">AI 感知中 · 置信度 98%</p>
                 </div>
+                <div className="flex items-center gap-1 mt-2 text-xs text-[#8c6a64] font-medium">
+                  <button
+                    onClick={() => setDate(shiftDate(date, -PERIOD_DAYS[period]))}
+                    className="p-1 rounded-full hover:bg-[#8c6a64]/10 transition-colors"
+                  >
+                    <ChevronLeft size={14} />
+                  </button>
+                  <span style={{ fontVariantNumeric: 'tabular-nums' }}>
+                    {period === 'day' ? (isToday ? '今天' : date) : `${periodStart} ~ ${date}`}
+                  </span>
+                  <button
+                    onClick={() => setDate(nextDate)}
+                    disabled={isToday}
+                    className="p-1 rounded-full hover:bg-[#8c6a64]/10 transition-colors disabled:opacity-30"
+                  >
+                    <ChevronRight size={14} />
+                  </button>
+                </div>
               </div>
-              <button 
-                onClick={onClose}
-                className="p-2 rounded-full hover:bg-[#8c6a64]/10 transition-colors text-[#8c6a64]"
+              <div className="flex gap-1">
+                <button
+                  onClick={handleExport}
+                  title="导出 CSV"
+                  className="p-2 rounded-full hover:bg-[#8c6a64]/10 transition-colors text-[#8c6a64]"
+                >
+                  <Download size={18} />
+                </button>
+                <button 
+                  onClick={onClose}
+                  className="p-2 rounded-full hover:bg-[#8c6a64]/10 transition-colors text-[#8c6a64]"
+                >
+                  <X size={20} />
+                </button>
+              </div>
+            </div>
+          </div>
+
+          {/* 报告周期切换 */}
+          <div className="px-6 pb-3 flex gap-2">
+            {(Object.keys(PERIOD_LABELS) as Period[]).map((p) => (
+              <button
+                key={p}
+                onClick={() => setPeriod(p)}
+                className={`px-3 py-1 rounded-full text-xs font-medium transition-colors ${
+                  period === p ? 'bg-rose-400 text-white' : 'text-[#8c6a64] hover:bg-[#8c6a64]/10'
+                }`}
               >
-                <X size={20} />
+                {PERIOD_LABELS[p]}
               </button>
-            </div>
+            ))}
           </div>
 
           {/* Content */}
           <div className="flex-1 overflow-y-auto px-6 pb-6 space-y-4 custom-scrollbar">
+            {period !== 'day' ? (
+              <PeriodReportView startDate={periodStart} endDate={date} />
+            ) : (<>
             {/* 核心指标卡片 (Bento Grid) */}
             <div className="grid grid-cols-2 gap-3">
               {/* 左：情绪指数 - 暖粉渐变 */}
@@ -156,7 +233,7 @@ export function ReportWindow({ onClose }: ReportWindowProps) {
                 <div className="absolute top-0 right-0 p-3 opacity-10 group-hover:opacity-20 transition-opacity">
                   <Brain size={48} color="#be185d" />
                 </div>
-                <p className="text-xs font-bold text-rose-400 mb-1">今日情绪</p>
+                <p className="text-xs font-bold text-rose-400 mb-1">{isToday ? '今日情绪' : '当日情绪'}</p>
                 <div className="text-4xl font-bold text-[#883446] mb-1" style={{ fontVariantNumeric: 'tabular-nums' }}>
                   {loading ? '...' : (countMood / 10).toFixed(1)}
                 </div>
@@ -219,7 +296,7 @@ export function ReportWindow({ onClose }: ReportWindowProps) {
               </div>
               
               <div className="w-full">
-                <EmotionChart onClose={() => {}} />
+                <EmotionChart onClose={() => {}} date={isToday ? undefined : date} />
               </div>
             </div>
 
@@ -247,6 +324,7 @@ export function ReportWindow({ onClose }: ReportWindowProps) {
                 </div>
               </div>
             </div>
+            </>)}
           </div>
         </div>
 
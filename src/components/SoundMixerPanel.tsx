@@ -0,0 +1,123 @@
+import { useState } from 'react';
+import { Volume2, VolumeX, Save, Trash2 } from 'lucide-react';
+import { useSounds } from '../hooks/useSounds';
+import { useSoundMixer } from '../hooks/useSoundMixer';
+import { FadeSettingsControl } from './FadeSettingsControl';
+
+// 混音面板：点选曲目叠加播放，每层单独调音量或静音
+export function SoundMixerPanel() {
+  const sounds = useSounds();
+  const {
+    mixer,
+    mixes,
+    error,
+    toggleLayer,
+    setLayerVolume,
+    setLayerMuted,
+    setMasterVolume,
+    loadMix,
+    saveMix,
+    deleteMix,
+  } = useSoundMixer();
+  const [mixName, setMixName] = useState('');
+
+  const handleSave = async () => {
+    await saveMix(mixName);
+    setMixName('');
+  };
+
+  return (
+    <div className="flex-1 overflow-y-auto space-y-3">
+      {/* 曲目层 */}
+      <div className="space-y-2">
+        {sounds.map((sound) => {
+          const layer = mixer.layers.find((l) => l.sound_id === sound.id);
+          return (
+            <div key={sound.id} className="bg-white/80 rounded-lg p-2 flex items-center gap-2">
+              <button
+                onClick={() => toggleLayer(sound.id)}
+                className={`w-8 h-8 rounded-full text-lg transition-all ${
+                  layer ? 'bg-rose-100 ring-2 ring-rose-400' : 'bg-gray-100 opacity-60 hover:opacity-100'
+                }`}
+                title={sound.name}
+              >
+                {sound.emoji}
+              </button>
+              <input
+                type="range"
+                min="0"
+                max="1"
+                step="0.01"
+                value={layer?.volume ?? 0.5}
+                disabled={!layer}
+                onChange={(e) => setLayerVolume(sound.id, parseFloat(e.target.value))}
+                className="flex-1 h-1.5 bg-gray-200 rounded-lg appearance-none cursor-pointer accent-rose-400 disabled:opacity-40"
+              />
+              <button
+                onClick={() => layer && setLayerMuted(sound.id, !layer.muted)}
+                disabled={!layer}
+                className="p-1 text-gray-600 disabled:opacity-30"
+              >
+                {layer?.muted ? <VolumeX size={14} /> : <Volume2 size={14} />}
+              </button>
+            </div>
+          );
+        })}
+      </div>
+
+      {/* 总音量 */}
+      <div className="bg-white/80 rounded-lg p-2.5 space-y-1.5">
+        <div className="flex items-center justify-between text-xs text-gray-700">
+          <span className="font-medium">总音量</span>
+          <span className="text-gray-600">{Math.round(mixer.master_volume * 100)}%</span>
+        </div>
+        <input
+          type="range"
+          min="0"
+          max="1"
+          step="0.01"
+          value={mixer.master_volume}
+          onChange={(e) => setMasterVolume(parseFloat(e.target.value))}
+          className="w-full h-1.5 bg-gray-200 rounded-lg appearance-none cursor-pointer accent-rose-400"
+        />
+      </div>
+
+      {/* 已保存的混音 */}
+      <div className="bg-white/80 rounded-lg p-2.5 space-y-2">
+        <div className="flex gap-1.5">
+          <input
+            value={mixName}
+            onChange={(e) => setMixName(e.target.value)}
+            placeholder="给当前混音起个名字"
+            maxLength={40}
+            className="flex-1 text-xs px-2 py-1 rounded border border-gray-200 focus:outline-none focus:border-rose-300"
+          />
+          <button
+            onClick={handleSave}
+            disabled={!mixName.trim() || mixer.layers.length === 0}
+            className="p-1.5 rounded bg-rose-100 text-rose-500 disabled:opacity-40"
+          >
+            <Save size={14} />
+          </button>
+        </div>
+        {mixes.map((mix) => (
+          <div key={mix.name} className="flex items-center justify-between text-xs text-gray-700">
+            <button onClick={() => loadMix(mix.name)} className="hover:text-rose-500">
+              {mix.name}
+              <span className="ml-1 text-gray-400">
+                {mix.layers.map((l) => sounds.find((s) => s.id === l.sound_id)?.emoji ?? '').join('')}
+              </span>
+            </button>
+            <button onClick={() => deleteMix(mix.name)} className="p-1 text-gray-400 hover:text-rose-400">
+              <Trash2 size={12} />
+            </button>
+          </div>
+        ))}
+        {error && <p className="text-[10px] text-red-400">{error}</p>}
+      </div>
+
+      {/* 渐变时长 */}
+      <FadeSettingsControl />
+    </div>
+  );
+}
@@ -0,0 +1,59 @@
+import { useState } from 'react';
+import { Timer, X } from 'lucide-react';
+import { usePlaybackStatus } from '../hooks/usePlaybackStatus';
+
+const PRESET_MINUTES = [15, 25, 45, 60];
+
+const formatRemaining = (seconds: number) => {
+  const h = Math.floor(seconds / 3600);
+  const m = Math.floor((seconds % 3600) / 60);
+  const s = seconds % 60;
+  const mmss = `${String(m).padStart(2, '0')}:${String(s).padStart(2, '0')}`;
+  return h > 0 ? `${h}:${mmss}` : mmss;
+};
+
+// 定时停止白噪音：预设时长或指定时间
+export function SoundTimerControl() {
+  const { status, remaining, error, refetch, startTimer, stopAt, cancelTimer } = usePlaybackStatus();
+  const [until, setUntil] = useState('');
+
+  return (
+    <div className="bg-white/80 rounded-lg p-2.5 space-y-1.5 mt-3" onMouseEnter={refetch}>
+      <div className="flex items-center justify-between text-xs text-gray-700">
+        <div className="flex items-center gap-1.5">
+          <Timer size={14} />
+          <span className="font-medium">定时停止</span>
+        </div>
+        {remaining !== null && (
+          <span className="flex items-center gap-1 text-rose-500" style={{ fontVariantNumeric: 'tabular-nums' }}>
+            {formatRemaining(remaining)}
+            <button onClick={cancelTimer} className="p-0.5 text-gray-400 hover:text-rose-400">
+              <X size={12} />
+            </button>
+          </span>
+        )}
+      </div>
+      <div className="flex items-center gap-1.5">
+        {PRESET_MINUTES.map((minutes) => (
+          <button
+            key={minutes}
+            onClick={() => startTimer(minutes)}
+            disabled={!status?.playing}
+            className="flex-1 text-[11px] py-1 rounded bg-gray-100 hover:bg-rose-100 text-gray-700 disabled:opacity-40"
+          >
+            {minutes}分
+          </button>
+        ))}
+        <input
+          type="time"
+          value={until}
+          onChange={(e) => setUntil(e.target.value)}
+          onBlur={() => until && status?.playing && stopAt(until)}
+          disabled={!status?.playing}
+          className="w-20 text-[11px] px-1 py-0.5 rounded border border-gray-200 disabled:opacity-40"
+        />
+      </div>
+      {error && <p className="text-[10px] text-red-400">{error}</p>}
+    </div>
+  );
+}
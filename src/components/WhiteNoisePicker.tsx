@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import { motion, AnimatePresence } from 'framer-motion';
 import { X, Volume2 } from 'lucide-react';
+import { useSounds } from '../hooks/useSounds';
 
 interface WhiteNoisePickerProps {
   isOpen: boolean;
@@ -11,14 +12,6 @@ interface WhiteNoisePickerProps {
   onVolumeChange: (volume: number) => void;
 }
 
-const sounds = [
-  { id: 'rain', name: '雨声', emoji: '🌧️', file: '/src/assets/sounds/rain.mp3' },
-  { id: 'ocean', name: '海浪', emoji: '🌊', file: '/src/assets/sounds/ocean.mp3' },
-  { id: 'forest', name: '森林', emoji: '🌲', file: '/src/assets/sounds/forest.mp3' },
-  { id: 'fire', name: '火焰', emoji: '🔥', file: '/src/assets/sounds/fire.mp3' },
-  { id: 'cafe', name: '咖啡厅', emoji: '☕', file: '/src/assets/sounds/cafe.mp3' },
-];
-
 export function WhiteNoisePicker({
   isOpen,
   onClose,
@@ -27,6 +20,8 @@ export function WhiteNoisePicker({
   volume,
   onVolumeChange,
 }: WhiteNoisePickerProps) {
+  const sounds = useSounds();
+
   return (
     <AnimatePresence>
       {isOpen && (
@@ -103,5 +98,3 @@ export function WhiteNoisePicker({
     </AnimatePresence>
   );
 }
-
-export { sounds };
@@ -1,14 +1,18 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+import { errorMessage } from '../utils/errors';
+import { todayString } from '../utils/dateUtils';
+import type { EmotionAnalysis } from '../bindings/EmotionAnalysis';
+import type { AnalysisUpdate } from '../bindings/AnalysisUpdate';
 
-export interface EmotionAnalysis {
-  emotion_index: number;      // 情绪指数 (1-10)
-  stress_level: number;        // 压力水平 (0-100)
-  total_records: number;       // 总记录数
-  valid_records: number;       // 有效记录数
-}
+// 类型由 Rust 端 ts-rs 生成（cargo test 时更新 src/bindings）
+export type { EmotionAnalysis, AnalysisUpdate };
+
+// date 为 YYYY-MM-DD，不传则为今天；只有今天会随事件实时更新
+export function useEmotionAnalysis(autoRefreshInterval: number = 0, date?: string) {
+  const live = !date || date === todayString();
 
-export function useEmotionAnalysis(autoRefreshInterval: number = 30000) {
   const [analysis, setAnalysis] = useState<EmotionAnalysis | null>(null);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
@@ -16,12 +20,13 @@ export function useEmotionAnalysis(autoRefreshInterval: number = 30000) {
   const fetchAnalysis = async () => {
     try {
       setLoading(true);
-      const result = await invoke<string>('analyze_today_emotion');
-      const data: EmotionAnalysis = JSON.parse(result);
+      const data = live
+        ? await invoke<EmotionAnalysis>('analyze_today_emotion')
+        : await invoke<EmotionAnalysis>('analyze_emotion_by_date', { date });
       setAnalysis(data);
       setError(null);
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
       console.error('Failed to fetch emotion analysis:', err);
     } finally {
       setLoading(false);
@@ -32,12 +37,25 @@ export function useEmotionAnalysis(autoRefreshInterval: number = 30000) {
     // 立即获取一次数据
     fetchAnalysis();
 
-    // 如果设置了自动刷新间隔，则定时刷新
+    // 数据由事件推送；设置了自动刷新间隔时额外定时刷新作为兜底
     if (autoRefreshInterval > 0) {
       const intervalId = setInterval(fetchAnalysis, autoRefreshInterval);
       return () => clearInterval(intervalId);
     }
-  }, [autoRefreshInterval]);
+  }, [autoRefreshInterval, date]);
+
+  // 每次新记录入库后由后端推送，所有窗口同步更新
+  useEffect(() => {
+    if (!live) return;
+    const unlisten = listen<AnalysisUpdate>('analysis-updated', (event) => {
+      setAnalysis(event.payload.analysis);
+      setError(null);
+      setLoading(false);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [live]);
 
   return {
     analysis,
@@ -1,74 +1,81 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+import { MoodPulseError, errorMessage, isMoodPulseError } from '../utils/errors';
+import type { DetectionResult } from '../bindings/DetectionResult';
+import type { DetectedEmotion } from '../bindings/DetectedEmotion';
 
-export interface EmotionData {
-  emotion: 'happy' | 'calm' | 'worried' | 'tired';
-  confidence: number;
-  source: 'fer' | 'deepface';
-}
+export type { DetectionResult };
+export type EmotionData = DetectedEmotion;
 
-export interface DetectionResult {
-  has_face: boolean;
-  is_away: boolean;
-  work_minutes: number;
-  need_break_alert: boolean;
-  emotions: EmotionData[];
-}
 
 export function useEmotionDetection(interval: number = 5000, paused: boolean = false) {
   const [emotionData, setEmotionData] = useState<DetectionResult | null>(null);
   const [isDetecting, setIsDetecting] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [stalled, setStalled] = useState(false);
   const isDetectingRef = useRef(false);
 
+  const applyError = useCallback((err: unknown) => {
+    const timedOut = isMoodPulseError(err) && err.code === 'backend_timeout';
+    setStalled(timedOut);
+    setError(timedOut ? 'detector stalled' : errorMessage(err));
+    console.error('Emotion detection error:', err);
+  }, []);
+
+  // 手动触发一次检测（定时检测由Rust端调度器负责）
   const detectEmotion = useCallback(async () => {
     if (isDetectingRef.current) return; // Skip if already detecting
 
     try {
       isDetectingRef.current = true;
       setIsDetecting(true);
-      const result = await invoke<string>('detect_emotion');
-      const data: DetectionResult = JSON.parse(result);
+      const data = await invoke<DetectionResult>('detect_emotion');
       setEmotionData(data);
       setError(null);
+      setStalled(false);
     } catch (err) {
-      setError(err as string);
-      console.error('Emotion detection error:', err);
+      applyError(err);
     } finally {
       isDetectingRef.current = false;
       setIsDetecting(false);
     }
-  }, []);
+  }, [applyError]);
 
+  // 订阅调度器推送的检测结果
   useEffect(() => {
-    let timeoutId: number;
-    let isActive = true;
-
-    const scheduleNextDetection = async () => {
-      if (!isActive || paused) return;
-
-      await detectEmotion();
+    const unlistenDetected = listen<DetectionResult>('emotion-detected', (event) => {
+      setEmotionData(event.payload);
+      setError(null);
+      setStalled(false);
+    });
+    const unlistenError = listen<MoodPulseError>('emotion-detection-error', (event) => {
+      applyError(event.payload);
+    });
 
-      if (isActive && !paused) {
-        timeoutId = setTimeout(scheduleNextDetection, interval);
-      }
+    return () => {
+      unlistenDetected.then((unlisten) => unlisten());
+      unlistenError.then((unlisten) => unlisten());
     };
+  }, [applyError]);
 
-    // Start first detection only if not paused
-    if (!paused) {
-      scheduleNextDetection();
-    }
+  useEffect(() => {
+    invoke('set_detection_interval', { intervalMs: interval }).catch((err) => {
+      console.error('Failed to set detection interval:', err);
+    });
+  }, [interval]);
 
-    return () => {
-      isActive = false;
-      if (timeoutId) clearTimeout(timeoutId);
-    };
-  }, [detectEmotion, interval, paused]);
+  useEffect(() => {
+    invoke(paused ? 'pause_detection' : 'resume_detection').catch((err) => {
+      console.error('Failed to update detection schedule:', err);
+    });
+  }, [paused]);
 
   return {
     emotionData,
     isDetecting,
     error,
+    stalled,
     detectEmotion
   };
 }
@@ -1,14 +1,17 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+import { errorMessage } from '../utils/errors';
+import { todayString } from '../utils/dateUtils';
+import type { TimelinePoint } from '../bindings/TimelinePoint';
+import type { AnalysisUpdate } from '../bindings/AnalysisUpdate';
 
-export interface TimelinePoint {
-  time: string;
-  value: number;
-  emoji: string;
-  emotion: string;
-}
+export type { TimelinePoint };
+
+// date 为 YYYY-MM-DD，不传则为今天；只有今天会随事件实时更新
+export function useEmotionTimeline(autoRefreshInterval: number = 0, date?: string) {
+  const live = !date || date === todayString();
 
-export function useEmotionTimeline(autoRefreshInterval: number = 30000) {
   const [timeline, setTimeline] = useState<TimelinePoint[]>([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
@@ -16,12 +19,13 @@ export function useEmotionTimeline(autoRefreshInterval: number = 30000) {
   const fetchTimeline = async () => {
     try {
       setLoading(true);
-      const result = await invoke<string>('get_emotion_timeline_data');
-      const data: TimelinePoint[] = JSON.parse(result);
+      const data = live
+        ? await invoke<TimelinePoint[]>('get_emotion_timeline_data')
+        : await invoke<TimelinePoint[]>('get_emotion_timeline_by_date', { date });
       setTimeline(data);
       setError(null);
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
       console.error('Failed to fetch emotion timeline:', err);
     } finally {
       setLoading(false);
@@ -34,7 +38,20 @@ export function useEmotionTimeline(autoRefreshInterval: number = 30000) {
       const intervalId = setInterval(fetchTimeline, autoRefreshInterval);
       return () => clearInterval(intervalId);
     }
-  }, [autoRefreshInterval]);
+  }, [autoRefreshInterval, date]);
+
+  // 每次新记录入库后由后端推送，所有窗口同步更新
+  useEffect(() => {
+    if (!live) return;
+    const unlisten = listen<AnalysisUpdate>('analysis-updated', (event) => {
+      setTimeline(event.payload.timeline);
+      setError(null);
+      setLoading(false);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [live]);
 
   return {
     timeline,
@@ -1,14 +1,16 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+import { errorMessage } from '../utils/errors';
+import { todayString } from '../utils/dateUtils';
+import type { FocusAnalysis } from '../bindings/FocusAnalysis';
 
-export interface FocusAnalysis {
-  total_focus_sessions: number;  // 今日专注次数
-  current_focus_duration: number; // 当前专注时长（分钟）
-  is_currently_focusing: boolean; // 是否正在专注
-  total_focus_time: number;       // 今日总专注时长（分钟）
-}
+export type { FocusAnalysis };
+
+// date 为 YYYY-MM-DD，不传则为今天；只有今天会随事件实时更新
+export function useFocusAnalysis(autoRefreshInterval: number = 0, date?: string) {
+  const live = !date || date === todayString();
 
-export function useFocusAnalysis(autoRefreshInterval: number = 30000) {
   const [focusData, setFocusData] = useState<FocusAnalysis | null>(null);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
@@ -16,12 +18,13 @@ export function useFocusAnalysis(autoRefreshInterval: number = 30000) {
   const fetchFocusData = async () => {
     try {
       setLoading(true);
-      const result = await invoke<string>('analyze_focus_time_today');
-      const data: FocusAnalysis = JSON.parse(result);
+      const data = live
+        ? await invoke<FocusAnalysis>('analyze_focus_time_today')
+        : await invoke<FocusAnalysis>('analyze_focus_time_by_date', { date });
       setFocusData(data);
       setError(null);
     } catch (err) {
-      setError(err as string);
+      setError(errorMessage(err));
       console.error('Failed to fetch focus analysis:', err);
     } finally {
       setLoading(false);
@@ -34,7 +37,20 @@ export function useFocusAnalysis(autoRefreshInterval: number = 30000) {
       const intervalId = setInterval(fetchFocusData, autoRefreshInterval);
       return () => clearInterval(intervalId);
     }
-  }, [autoRefreshInterval]);
+  }, [autoRefreshInterval, date]);
+
+  // 每次新记录入库后由后端推送，所有窗口同步更新
+  useEffect(() => {
+    if (!live) return;
+    const unlisten = listen<FocusAnalysis>('focus-changed', (event) => {
+      setFocusData(event.payload);
+      setError(null);
+      setLoading(false);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [live]);
 
   return {
     focusData,
@@ -0,0 +1,42 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/tauri';
+import { errorMessage } from '../utils/errors';
+import type { PeriodReport } from '../bindings/PeriodReport';
+import type { DayScore } from '../bindings/DayScore';
+import type { EmotionShare } from '../bindings/EmotionShare';
+import type { PeriodChange } from '../bindings/PeriodChange';
+import type { WeekSummary } from '../bindings/WeekSummary';
+
+export type { PeriodReport, DayScore, EmotionShare, PeriodChange, WeekSummary };
+
+// 周报/月报，日期格式 YYYY-MM-DD（含首尾两天）
+export function usePeriodReport(startDate: string, endDate: string) {
+  const [report, setReport] = useState<PeriodReport | null>(null);
+  const [loading, setLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetchReport = async () => {
+    try {
+      setLoading(true);
+      const data = await invoke<PeriodReport>('build_period_report', { startDate, endDate });
+      setReport(data);
+      setError(null);
+    } catch (err) {
+      setError(errorMessage(err));
+      console.error('Failed to build period report:', err);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    fetchReport();
+  }, [startDate, endDate]);
+
+  return {
+    report,
+    loading,
+    error,
+    refetch: fetchReport
+  };
+}
@@ -0,0 +1,60 @@
+import { useState, useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { errorMessage } from '../utils/errors';
+import {
+  getPlaybackStatus,
+  setSoundTimer,
+  cancelSoundTimer,
+  type PlaybackStatus,
+} from '../utils/tauriApi';
+
+export type { PlaybackStatus };
+
+// 播放状态和定时器；剩余时间按 ends_at 在本地每秒刷新，定时器由后端执行，窗口隐藏也会生效
+export function usePlaybackStatus() {
+  const [status, setStatus] = useState<PlaybackStatus | null>(null);
+  const [remaining, setRemaining] = useState<number | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const run = async (action: () => Promise<PlaybackStatus>) => {
+    try {
+      setStatus(await action());
+      setError(null);
+    } catch (err) {
+      setError(errorMessage(err));
+      console.error('Sound timer error:', err);
+    }
+  };
+
+  useEffect(() => {
+    run(getPlaybackStatus);
+    const unlisten = listen<PlaybackStatus>('sound-timer-finished', (event) => {
+      setStatus(event.payload);
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    const endsAt = status?.timer?.ends_at;
+    if (endsAt === undefined) {
+      setRemaining(null);
+      return;
+    }
+    const tick = () => setRemaining(Math.max(0, endsAt - Math.floor(Date.now() / 1000)));
+    tick();
+    const interval = setInterval(tick, 1000);
+    return () => clearInterval(interval);
+  }, [status?.timer?.ends_at]);
+
+  return {
+    status,
+    remaining,
+    error,
+    refetch: () => run(getPlaybackStatus),
+    startTimer: (minutes: number) => run(() => setSoundTimer({ minutes })),
+    stopAt: (until: string) => run(() => setSoundTimer({ until })),
+    cancelTimer: () => run(cancelSoundTimer),
+  };
+}
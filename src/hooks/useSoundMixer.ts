@@ -0,0 +1,77 @@
+import { useState, useEffect } from 'react';
+import { errorMessage } from '../utils/errors';
+import {
+  getMixerState,
+  addSoundLayer,
+  removeSoundLayer,
+  setSoundLayerVolume,
+  setSoundLayerMuted,
+  setMasterVolume,
+  saveSoundMix,
+  loadSoundMix,
+  listSoundMixes,
+  deleteSoundMix,
+  type MixerState,
+  type SoundMix,
+} from '../utils/tauriApi';
+
+export type { MixerState, SoundMix };
+
+// 混音器状态和已保存的混音；每次操作都以后端返回的状态为准
+export function useSoundMixer() {
+  const [mixer, setMixer] = useState<MixerState>({ master_volume: 1, layers: [] });
+  const [mixes, setMixes] = useState<SoundMix[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const run = async (action: () => Promise<MixerState>) => {
+    try {
+      setMixer(await action());
+      setError(null);
+    } catch (err) {
+      setError(errorMessage(err));
+      console.error('Mixer error:', err);
+    }
+  };
+
+  const refreshMixes = async () => {
+    setMixes(await listSoundMixes());
+  };
+
+  useEffect(() => {
+    run(getMixerState);
+    refreshMixes();
+  }, []);
+
+  const toggleLayer = (id: string) => {
+    const playing = mixer.layers.some((layer) => layer.sound_id === id);
+    return run(() => (playing ? removeSoundLayer(id) : addSoundLayer(id, 0.5)));
+  };
+
+  const saveMix = async (name: string) => {
+    try {
+      await saveSoundMix(name);
+      setError(null);
+      await refreshMixes();
+    } catch (err) {
+      setError(errorMessage(err));
+    }
+  };
+
+  const deleteMix = async (name: string) => {
+    await deleteSoundMix(name);
+    await refreshMixes();
+  };
+
+  return {
+    mixer,
+    mixes,
+    error,
+    toggleLayer,
+    setLayerVolume: (id: string, volume: number) => run(() => setSoundLayerVolume(id, volume)),
+    setLayerMuted: (id: string, muted: boolean) => run(() => setSoundLayerMuted(id, muted)),
+    setMasterVolume: (volume: number) => run(() => setMasterVolume(volume)),
+    loadMix: (name: string) => run(() => loadSoundMix(name)),
+    saveMix,
+    deleteMix,
+  };
+}
@@ -0,0 +1,15 @@
+import { useState, useEffect } from 'react';
+import { listSounds, type SoundInfo } from '../utils/tauriApi';
+
+export type { SoundInfo };
+
+// 后端声音目录，只保留安装包里实际存在的曲目
+export function useSounds() {
+  const [sounds, setSounds] = useState<SoundInfo[]>([]);
+
+  useEffect(() => {
+    listSounds().then((all) => setSounds(all.filter((sound) => sound.available)));
+  }, []);
+
+  return sounds;
+}
@@ -0,0 +1,16 @@
+// 日期工具，格式与后端一致：本地时间 YYYY-MM-DD
+
+export function toDateString(date: Date): string {
+  const pad = (n: number) => String(n).padStart(2, '0')
+  return `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`
+}
+
+export function todayString(): string {
+  return toDateString(new Date())
+}
+
+// 将 YYYY-MM-DD 前后移动若干天
+export function shiftDate(date: string, days: number): string {
+  const [year, month, day] = date.split('-').map(Number)
+  return toDateString(new Date(year, month - 1, day + days))
+}
@@ -0,0 +1,29 @@
+// 后端命令统一返回的错误，对应 src-tauri/src/error.rs 中的 MoodPulseError
+
+import type { MoodPulseError } from '../bindings/MoodPulseError';
+
+export type { MoodPulseError };
+export type MoodPulseErrorCode = MoodPulseError['code'];
+
+const MESSAGES: Record<MoodPulseErrorCode, string> = {
+  database: '数据读写失败',
+  backend_unavailable: '情绪检测服务不可用',
+  backend_timeout: '情绪检测无响应',
+  camera: '无法访问摄像头',
+  audio_device: '无法播放声音',
+  serialization: '数据格式错误',
+  io: '文件读写失败',
+  validation: '参数无效',
+};
+
+export function isMoodPulseError(err: unknown): err is MoodPulseError {
+  return typeof err === 'object' && err !== null && 'code' in err && 'message' in err;
+}
+
+// 将 invoke 抛出的错误转换为可展示的中文提示
+export function errorMessage(err: unknown): string {
+  if (isMoodPulseError(err)) {
+    return MESSAGES[err.code] ?? err.message;
+  }
+  return String(err);
+}
@@ -1,30 +1,240 @@
 // Tauri API 封装
 
 import { invoke } from '@tauri-apps/api/tauri'
+import { save } from '@tauri-apps/api/dialog'
+import type { EmotionEntry } from '../bindings/EmotionEntry'
+import type { MoodCheckin } from '../bindings/MoodCheckin'
+import type { EmotionRollup } from '../bindings/EmotionRollup'
+import type { RetentionPolicy } from '../bindings/RetentionPolicy'
+import type { RetentionSummary } from '../bindings/RetentionSummary'
+import type { DailySummary } from '../bindings/DailySummary'
+import type { ExportFormat } from '../bindings/ExportFormat'
+import type { ExportSummary } from '../bindings/ExportSummary'
+import type { SoundInfo } from '../bindings/SoundInfo'
+import type { MixLayer } from '../bindings/MixLayer'
+import type { MixerState } from '../bindings/MixerState'
+import type { SoundMix } from '../bindings/SoundMix'
+import type { FadeSettings } from '../bindings/FadeSettings'
+import type { SleepTimer } from '../bindings/SleepTimer'
+import type { PlaybackStatus } from '../bindings/PlaybackStatus'
 
-export interface EmotionData {
-  timestamp: string
-  emotion: string
-  confidence: number
+export type {
+  MoodCheckin, EmotionRollup, RetentionPolicy, RetentionSummary, DailySummary, ExportFormat, ExportSummary,
+  SoundInfo, MixLayer, MixerState, SoundMix, FadeSettings, SleepTimer, PlaybackStatus,
 }
 
-// 保存情绪数据
-export async function saveEmotionData(data: EmotionData[]): Promise<void> {
+// 前端录入或导入的情绪记录；datetime 为本地时间 "YYYY-MM-DD HH:MM:SS" 或 RFC 3339
+export type EmotionData = EmotionEntry
+
+// 保存情绪数据，返回新记录的 id；任何一条无效则全部不保存
+export async function saveEmotionData(data: EmotionData[]): Promise<number[]> {
   try {
-    await invoke('save_emotion_data', { data: JSON.stringify(data) })
+    return await invoke<number[]>('save_emotion_data', { data })
   } catch (error) {
     console.error('Failed to save emotion data:', error)
     throw error
   }
 }
 
-// 加载情绪数据
-export async function loadEmotionData(): Promise<EmotionData[]> {
+// 加载情绪数据，可按时间范围过滤，includeDetected 时合并摄像头检测记录
+export async function loadEmotionData(
+  startDate?: string,
+  endDate?: string,
+  includeDetected = false
+): Promise<EmotionData[]> {
   try {
-    const result = await invoke<string>('load_emotion_data')
-    return JSON.parse(result)
+    return await invoke<EmotionData[]>('load_emotion_data', { startDate, endDate, includeDetected })
   } catch (error) {
     console.error('Failed to load emotion data:', error)
     return []
   }
 }
+
+// 记录一次心情打卡，intensity 取 1-10，note 可选
+export async function recordMoodCheckin(
+  emotion: string,
+  intensity: number,
+  note?: string
+): Promise<MoodCheckin> {
+  try {
+    return await invoke<MoodCheckin>('record_mood_checkin', { emotion, intensity, note })
+  } catch (error) {
+    console.error('Failed to record mood checkin:', error)
+    throw error
+  }
+}
+
+// 加载指定时间范围内的心情打卡
+export async function getMoodCheckins(startDate: string, endDate: string): Promise<MoodCheckin[]> {
+  try {
+    return await invoke<MoodCheckin[]>('get_mood_checkins', { startDate, endDate })
+  } catch (error) {
+    console.error('Failed to load mood checkins:', error)
+    return []
+  }
+}
+
+// 打卡在情绪指数中的权重，0 表示只看摄像头检测
+export async function getCheckinWeight(): Promise<number> {
+  return await invoke<number>('get_checkin_weight')
+}
+
+export async function setCheckinWeight(weight: number): Promise<void> {
+  await invoke('set_checkin_weight', { weight })
+}
+
+// 原始检测记录保留天数，0 表示永久保留
+export async function getRetentionPolicy(): Promise<RetentionPolicy> {
+  return await invoke<RetentionPolicy>('get_retention_policy')
+}
+
+// 修改保留天数，并立即把过期记录压缩为半小时汇总
+export async function setRetentionPolicy(rawDays: number): Promise<RetentionSummary> {
+  return await invoke<RetentionSummary>('set_retention_policy', { rawDays })
+}
+
+// 加载已压缩的半小时汇总，用于长期报表
+export async function getEmotionRollups(startDate: string, endDate: string): Promise<EmotionRollup[]> {
+  try {
+    return await invoke<EmotionRollup[]>('get_emotion_rollups', { startDate, endDate })
+  } catch (error) {
+    console.error('Failed to load emotion rollups:', error)
+    return []
+  }
+}
+
+// 按天加载情绪汇总（日期格式 YYYY-MM-DD），用于日历热力图；没有数据的日期不返回
+export async function getDailySummaries(startDate: string, endDate: string): Promise<DailySummary[]> {
+  try {
+    return await invoke<DailySummary[]>('get_daily_summaries', { startDate, endDate })
+  } catch (error) {
+    console.error('Failed to load daily summaries:', error)
+    return []
+  }
+}
+
+const EXPORT_EXTENSIONS: Record<ExportFormat, string> = {
+  csv: 'csv',
+  json: 'json',
+  json_lines: 'jsonl',
+}
+
+// 导出检测记录：先弹出保存对话框，用户取消时返回 null
+// startDate/endDate 为 "YYYY-MM-DD HH:MM:SS"，fields 为空时导出全部字段
+export async function exportEmotionRecords(
+  format: ExportFormat,
+  startDate?: string,
+  endDate?: string,
+  fields?: string[]
+): Promise<ExportSummary | null> {
+  const extension = EXPORT_EXTENSIONS[format]
+  const path = await save({
+    defaultPath: `moodpulse-emotions.${extension}`,
+    filters: [{ name: format.toUpperCase(), extensions: [extension] }],
+  })
+  if (!path) {
+    return null
+  }
+  return await invoke<ExportSummary>('export_emotion_records', { path, format, startDate, endDate, fields })
+}
+
+// 白噪音曲目列表；available 为 false 表示安装包里缺少该文件
+export async function listSounds(): Promise<SoundInfo[]> {
+  try {
+    return await invoke<SoundInfo[]>('list_sounds')
+  } catch (error) {
+    console.error('Failed to list sounds:', error)
+    return []
+  }
+}
+
+// 循环播放指定曲目，替换正在播放的声音；volume 范围 0-1
+export async function playSound(id: string, volume: number): Promise<void> {
+  await invoke('play_sound', { id, volume })
+}
+
+export async function stopSound(): Promise<void> {
+  await invoke('stop_white_noise')
+}
+
+export async function setSoundVolume(volume: number): Promise<void> {
+  await invoke('set_white_noise_volume', { volume })
+}
+
+// 混音：多个曲目同时播放，每层音量 = 层音量 × 总音量
+export async function getMixerState(): Promise<MixerState> {
+  return await invoke<MixerState>('get_mixer_state')
+}
+
+// 加入一层；已在播放的曲目只更新音量
+export async function addSoundLayer(id: string, volume: number): Promise<MixerState> {
+  return await invoke<MixerState>('add_sound_layer', { id, volume })
+}
+
+export async function removeSoundLayer(id: string): Promise<MixerState> {
+  return await invoke<MixerState>('remove_sound_layer', { id })
+}
+
+export async function setSoundLayerVolume(id: string, volume: number): Promise<MixerState> {
+  return await invoke<MixerState>('set_sound_layer_volume', { id, volume })
+}
+
+export async function setSoundLayerMuted(id: string, muted: boolean): Promise<MixerState> {
+  return await invoke<MixerState>('set_sound_layer_muted', { id, muted })
+}
+
+export async function setMasterVolume(volume: number): Promise<MixerState> {
+  return await invoke<MixerState>('set_master_volume', { volume })
+}
+
+// 把当前混音保存为 name，同名覆盖
+export async function saveSoundMix(name: string): Promise<SoundMix> {
+  return await invoke<SoundMix>('save_sound_mix', { name })
+}
+
+export async function loadSoundMix(name: string): Promise<MixerState> {
+  return await invoke<MixerState>('load_sound_mix', { name })
+}
+
+export async function listSoundMixes(): Promise<SoundMix[]> {
+  try {
+    return await invoke<SoundMix[]>('list_sound_mixes')
+  } catch (error) {
+    console.error('Failed to list sound mixes:', error)
+    return []
+  }
+}
+
+export async function deleteSoundMix(name: string): Promise<boolean> {
+  return await invoke<boolean>('delete_sound_mix', { name })
+}
+
+// 淡入、淡出和切换曲目时的交叉淡化时长（毫秒），0 表示立即
+export async function getFadeSettings(): Promise<FadeSettings> {
+  return await invoke<FadeSettings>('get_fade_settings')
+}
+
+export async function setFadeSettings(settings: FadeSettings): Promise<FadeSettings> {
+  return await invoke<FadeSettings>('set_fade_settings', {
+    fadeInMs: settings.fade_in_ms,
+    fadeOutMs: settings.fade_out_ms,
+    crossfadeMs: settings.crossfade_ms,
+  })
+}
+
+// 当前播放状态和定时器剩余时间
+export async function getPlaybackStatus(): Promise<PlaybackStatus> {
+  return await invoke<PlaybackStatus>('get_playback_status')
+}
+
+// 定时停止：minutes 分钟后，或在 until（"HH:MM"，今天或明天）停止；fadeOut 为 false 时直接停止
+export async function setSoundTimer(
+  timer: { minutes: number } | { until: string },
+  fadeOut = true
+): Promise<PlaybackStatus> {
+  return await invoke<PlaybackStatus>('set_sound_timer', { ...timer, fadeOut })
+}
+
+export async function cancelSoundTimer(): Promise<PlaybackStatus> {
+  return await invoke<PlaybackStatus>('cancel_sound_timer')
+}